@@ -0,0 +1,84 @@
+//! Compares the memory footprint of the slot layout against the enum based layout used by
+//! earlier versions of the crate.
+//!
+//! A slot holds either its value or, while it is free, the link to the next free slot, so it is
+//! as large as the larger of the two, and the occupancy is tracked by a separate bitmap. The enum
+//! stored the same union, plus a discriminant and the padding after it. Only types with a niche
+//! that can hold the discriminant were stored without any overhead by the enum, so those grow by
+//! the bitmap.
+//!
+//! The bitmap takes one bit per slot, but stable Rust can't size an array using an expression of
+//! `N`, so `UnrestrictedSlots` reserves one byte per slot for it (the `array` column). `BoxSlots`
+//! allocates the exact bitmap, the `exact` column shows the size of the slots with it.
+//!
+//! ```text
+//! cargo run --example layout_sizes
+//! ```
+
+use core::mem::{size_of, ManuallyDrop};
+
+use slots::index::SlotIndex;
+use slots::unrestricted::UnrestrictedSlots;
+
+const N: usize = 64;
+
+/// The slot representation used by earlier versions: one enum per slot.
+#[allow(unused)]
+enum Entry<IT, I> {
+    Used(IT),
+    EmptyNext(I),
+    EmptyLast,
+}
+
+/// The slot representation of the current version, the occupancy is stored in a bitmap.
+#[allow(unused)]
+union Slot<IT, I: Copy> {
+    value: ManuallyDrop<IT>,
+    next: I,
+}
+
+#[allow(unused)]
+struct TwoNichesIn16Byte {
+    n1: u64,
+    n2: u32,
+    n3: u16,
+    n4: u8,
+    b: bool,
+}
+
+fn row<IT, I: SlotIndex>(name: &str) {
+    // both layouts need the top of the free stack, the number of touched slots and the count
+    let overhead = 3 * size_of::<I>();
+    let old = N * size_of::<Entry<IT, I>>() + overhead;
+    let array = size_of::<UnrestrictedSlots<IT, N, I>>();
+    let exact = N * size_of::<Slot<IT, I>>() + N.div_ceil(8) + overhead;
+
+    println!(
+        "{name:<24} {index:<6} {old:>8} {array:>8} {change:>+8.1}% {exact:>8} {exact_change:>+8.1}%",
+        index = core::any::type_name::<I>(),
+        change = (array as f64 / old as f64 - 1.0) * 100.0,
+        exact_change = (exact as f64 / old as f64 - 1.0) * 100.0,
+    );
+}
+
+fn main() {
+    println!("UnrestrictedSlots<IT, {N}, I>, sizes in bytes");
+    println!(
+        "{:<24} {:<6} {:>8} {:>8} {:>9} {:>8} {:>9}",
+        "IT", "I", "enum", "array", "change", "exact", "change"
+    );
+
+    row::<u8, u8>("u8");
+    row::<u8, u16>("u8");
+    row::<u8, usize>("u8");
+    row::<u16, u16>("u16");
+    row::<u32, u32>("u32");
+    row::<u32, usize>("u32");
+    row::<u64, u32>("u64");
+    row::<u64, usize>("u64");
+    row::<[u8; 3], u8>("[u8; 3]");
+    row::<[u8; 3], usize>("[u8; 3]");
+    row::<[u64; 4], usize>("[u64; 4]");
+    row::<char, u32>("char (niche)");
+    row::<TwoNichesIn16Byte, usize>("16 bytes, 2 niches");
+}
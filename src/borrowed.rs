@@ -0,0 +1,474 @@
+//! Slots objects that manage a buffer provided by the caller.
+//!
+//! The collections in this module don't own their storage. Instead, they are constructed from
+//! a mutable reference to a slice of uninitialized [`Slot`] objects, which may be placed anywhere,
+//! for example in a dedicated linker section or in DMA-capable memory. The capacity of the
+//! collection is the length of the buffer.
+//!
+//!  * [`BorrowedUnrestrictedSlots`] works like [`UnrestrictedSlots`]: elements are addressed
+//!    using a `usize` index and can be modified by anyone.
+//!  * [`BorrowedSlots`] works like [`Slots`]: elements can only be modified, or taken out, using
+//!    the [`Key`] that was returned when they were stored.
+//!
+//! ```rust
+//! use core::mem::{align_of, size_of, MaybeUninit};
+//! use slots::borrowed::{BorrowedSlots, Slot};
+//!
+//! let mut buffer = [const { MaybeUninit::<Slot<u32>>::uninit() }; 4];
+//! let mut slots: BorrowedSlots<u32> = BorrowedSlots::new(&mut buffer);
+//!
+//! assert_eq!(4, slots.capacity());
+//!
+//! let k = slots.store(5).unwrap();
+//! slots.modify(&k, |e| *e += 1);
+//!
+//! assert_eq!(6, slots.take(k));
+//! ```
+//!
+//! Elements that are still stored when the collection is dropped are dropped along with it.
+//! The buffer is then considered uninitialized again and can be reused.
+//!
+//! [`UnrestrictedSlots`]: crate::unrestricted::UnrestrictedSlots
+//! [`Slots`]: crate::slots::Slots
+
+use core::marker::PhantomData;
+use core::mem::{size_of, MaybeUninit};
+use core::ops::{Index, IndexMut};
+use core::slice;
+
+use crate::error::{SlotsError, TakeError};
+use crate::index::SlotIndex;
+use crate::iterator::{Iter, IterMut};
+use crate::keyed::{Keyed, RawAccess, RawKey};
+use crate::owner::{DefaultOwnerId, OwnerIdSource};
+use crate::raw::{bitmap_len, BorrowedStorage, Entry, RawSlots};
+
+/// A single slot of a buffer used by the collections in this module.
+///
+/// The contents of a slot are not accessible directly. The type only describes how much memory
+/// a slot needs: the collections store all the entries at the start of the buffer, followed by
+/// the occupancy bitmap.
+#[repr(C)]
+pub struct Slot<IT, I: SlotIndex = usize> {
+    _entry: Entry<IT, I>,
+    _bitmap: u8,
+}
+
+/// Splits the buffer into an array of entries and the occupancy bitmap.
+fn init_buffer<IT, I: SlotIndex>(
+    buffer: &mut [MaybeUninit<Slot<IT, I>>],
+) -> BorrowedStorage<'_, IT, I> {
+    assert!(
+        buffer.len() <= I::MAX_CAPACITY,
+        "The capacity can not be represented by the index type"
+    );
+
+    let len = buffer.len();
+    let start = buffer.as_mut_ptr().cast::<u8>();
+    let bitmap_len = bitmap_len(len);
+
+    // Safety: `Slot` is aligned for `Entry`, and holds an entry followed by a byte, so `len` slots
+    // are large enough to hold `len` entries followed by `len` bytes, and `bitmap_len` is never
+    // larger than `len`. Any contents are valid for a vacant entry.
+    unsafe {
+        let bitmap = start.add(len * size_of::<Entry<IT, I>>());
+        bitmap.write_bytes(0, bitmap_len);
+
+        BorrowedStorage {
+            entries: slice::from_raw_parts_mut(start.cast::<Entry<IT, I>>(), len),
+            bitmap: slice::from_raw_parts_mut(bitmap, bitmap_len),
+        }
+    }
+}
+
+/// Slots object that provides an unrestricted access control for the data stored in a
+/// caller-provided buffer.
+///
+/// The struct has three type parameters:
+///  - `'a` is the lifetime of the buffer
+///  - `IT` is the type of the stored data
+///  - `I` is the type used to store slot indices, see [`SlotIndex`]. Defaults to `usize`.
+///
+/// For more information, see the [module level documentation](crate::borrowed)
+pub struct BorrowedUnrestrictedSlots<'a, IT, I: SlotIndex = usize> {
+    raw: RawSlots<BorrowedStorage<'a, IT, I>>,
+}
+
+impl<'a, IT, I: SlotIndex> BorrowedUnrestrictedSlots<'a, IT, I> {
+    /// Creates a new, empty collection that stores its elements in `buffer`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of the buffer can not be represented by the index type.
+    ///
+    /// ```
+    /// # use core::mem::{align_of, size_of, MaybeUninit};
+    /// # use slots::borrowed::{BorrowedUnrestrictedSlots, Slot};
+    /// let mut buffer = [const { MaybeUninit::<Slot<u32>>::uninit() }; 4];
+    /// let mut slots = BorrowedUnrestrictedSlots::new(&mut buffer);
+    ///
+    /// let k = slots.store(3).unwrap();
+    ///
+    /// assert_eq!(Some(3), slots.take(k));
+    /// ```
+    pub fn new(buffer: &'a mut [MaybeUninit<Slot<IT, I>>]) -> Self {
+        Self {
+            raw: RawSlots::new(init_buffer(buffer)),
+        }
+    }
+
+    /// Returns a read-only iterator.
+    /// The iterator can be used to read data from all occupied slots.
+    ///
+    /// **Note:** Do not rely on the order in which the elements are returned.
+    pub fn iter(&self) -> Iter<'_, IT> {
+        self.raw.iter()
+    }
+
+    /// Returns a read-write iterator.
+    /// The iterator can be used to read and modify data from all occupied slots, but it can't remove data.
+    ///
+    /// **Note:** Do not rely on the order in which the elements are returned.
+    pub fn iter_mut(&mut self) -> IterMut<'_, IT> {
+        self.raw.iter_mut()
+    }
+
+    /// Returns the number of slots, which is the length of the buffer.
+    pub fn capacity(&self) -> usize {
+        self.raw.capacity()
+    }
+
+    /// Returns the number of occupied slots
+    pub fn count(&self) -> usize {
+        self.raw.count()
+    }
+
+    /// Returns whether all the slots are occupied and the next [`store()`](BorrowedUnrestrictedSlots::store) will fail.
+    pub fn is_full(&self) -> bool {
+        self.raw.is_full()
+    }
+
+    /// Store an element in a free slot and return the key to access it.
+    ///
+    /// Storing a variable takes ownership over it. If the storage is full,
+    /// the inserted data is returned in the return value.
+    pub fn store(&mut self, item: IT) -> Result<usize, IT> {
+        self.raw.store(item)
+    }
+
+    /// Store an element in a free slot and return the key to access it.
+    ///
+    /// If the storage is full, the inserted data is returned in [`SlotsError::Full`].
+    pub fn try_store(&mut self, item: IT) -> Result<usize, SlotsError<IT>> {
+        self.raw.store(item).map_err(SlotsError::Full)
+    }
+
+    /// Remove and return the element that belongs to the key.
+    ///
+    /// This operation is fallible. If `key` addresses a free slot or is outside the collection,
+    /// `None` is returned.
+    pub fn take(&mut self, key: usize) -> Option<IT> {
+        self.raw.take(key).ok()
+    }
+
+    /// Remove and return the element that belongs to the key.
+    ///
+    /// This operation is fallible. If `key` addresses a free slot, [`SlotsError::Vacant`] is
+    /// returned. If `key` is outside the collection, [`SlotsError::OutOfBounds`] is returned.
+    pub fn try_take(&mut self, key: usize) -> Result<IT, SlotsError<IT>> {
+        self.raw.take(key)
+    }
+
+    /// Read the element that belongs to a particular index. Since the index may point to
+    /// a free slot or outside the collection, this operation may return None without invoking the callback.
+    pub fn read<T>(&self, key: usize, function: impl FnOnce(&IT) -> T) -> Option<T> {
+        self.raw.get(key).ok().map(function)
+    }
+
+    /// Read the element that belongs to a particular index.
+    ///
+    /// This operation is fallible. If `key` addresses a free slot, [`SlotsError::Vacant`] is
+    /// returned. If `key` is outside the collection, [`SlotsError::OutOfBounds`] is returned.
+    pub fn try_read<T>(
+        &self,
+        key: usize,
+        function: impl FnOnce(&IT) -> T,
+    ) -> Result<T, SlotsError<IT>> {
+        self.raw.get(key).map(function)
+    }
+
+    /// Access the element that belongs to the key for modification.
+    ///
+    /// This operation is fallible. If `key` addresses a free slot or is outside the collection,
+    /// `None` is returned.
+    pub fn modify<T>(&mut self, key: usize, function: impl FnOnce(&mut IT) -> T) -> Option<T> {
+        self.raw.get_mut(key).ok().map(function)
+    }
+
+    /// Access the element that belongs to the key for modification.
+    ///
+    /// This operation is fallible. If `key` addresses a free slot, [`SlotsError::Vacant`] is
+    /// returned. If `key` is outside the collection, [`SlotsError::OutOfBounds`] is returned.
+    pub fn try_modify<T>(
+        &mut self,
+        key: usize,
+        function: impl FnOnce(&mut IT) -> T,
+    ) -> Result<T, SlotsError<IT>> {
+        self.raw.get_mut(key).map(function)
+    }
+
+    /// Returns a reference to the element that belongs to the key.
+    ///
+    /// This operation is fallible. If `key` addresses a free slot or is outside the collection,
+    /// `None` is returned.
+    pub fn get(&self, key: usize) -> Option<&IT> {
+        self.raw.get(key).ok()
+    }
+
+    /// Returns a mutable reference to the element that belongs to the key.
+    ///
+    /// This operation is fallible. If `key` addresses a free slot or is outside the collection,
+    /// `None` is returned.
+    pub fn get_mut(&mut self, key: usize) -> Option<&mut IT> {
+        self.raw.get_mut(key).ok()
+    }
+
+    /// Returns mutable references to multiple elements at once.
+    ///
+    /// This operation is fallible. If any of the `keys` address a free slot or are outside the
+    /// collection, or if the same key is present more than once, `None` is returned.
+    pub fn get_many_mut<const M: usize>(&mut self, keys: [usize; M]) -> Option<[&mut IT; M]> {
+        self.raw.get_many_mut(keys)
+    }
+}
+
+/// The key used to access stored elements.
+///
+/// **Important:** It should only be used to access the same collection that returned it.
+/// When the owner id strategy does not allocate ids (e.g. because the `runtime_checks` feature
+/// is disabled), extra care must be taken to ensure this constraint. See [`crate::owner`].
+///
+/// The index is stored as a non-zero value, so `Option<Key>` is the same size as `Key`.
+#[derive(Debug)]
+pub struct Key<IT, I: SlotIndex = usize, O: OwnerIdSource = DefaultOwnerId> {
+    raw: RawKey<I, O, ()>,
+    _item_marker: PhantomData<IT>,
+}
+
+impl<IT, I: SlotIndex, O: OwnerIdSource> Key<IT, I, O> {
+    fn new(raw: RawKey<I, O, ()>) -> Self {
+        Self {
+            raw,
+            _item_marker: PhantomData,
+        }
+    }
+
+    pub fn index(&self) -> usize {
+        self.raw.index()
+    }
+}
+
+impl<'a, IT, I: SlotIndex> RawAccess for BorrowedUnrestrictedSlots<'a, IT, I> {
+    type Storage = BorrowedStorage<'a, IT, I>;
+
+    fn raw(&self) -> &RawSlots<Self::Storage> {
+        &self.raw
+    }
+
+    fn raw_mut(&mut self) -> &mut RawSlots<Self::Storage> {
+        &mut self.raw
+    }
+}
+
+/// Slots object that provides strict access control for the data stored in a caller-provided
+/// buffer.
+///
+/// The struct has four type parameters:
+///  - `'a` is the lifetime of the buffer
+///  - `IT` is the type of the stored data
+///  - `I` is the type used to store slot indices, see [`SlotIndex`]. Defaults to `usize`.
+///  - `O` is the strategy used to allocate owner ids, see [`OwnerIdSource`]. Defaults to [`DefaultOwnerId`].
+///
+/// For more information, see the [module level documentation](crate::borrowed)
+pub struct BorrowedSlots<'a, IT, I: SlotIndex = usize, O: OwnerIdSource = DefaultOwnerId> {
+    keyed: Keyed<BorrowedUnrestrictedSlots<'a, IT, I>, (), O>,
+}
+
+impl<'a, IT, I: SlotIndex, O: OwnerIdSource> BorrowedSlots<'a, IT, I, O> {
+    /// Creates a new, empty collection that stores its elements in `buffer`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of the buffer can not be represented by the index type.
+    pub fn new(buffer: &'a mut [MaybeUninit<Slot<IT, I>>]) -> Self {
+        Self {
+            keyed: Keyed::new(BorrowedUnrestrictedSlots::new(buffer), ()),
+        }
+    }
+
+    /// Returns a read-only iterator.
+    /// The iterator can be used to read data from all occupied slots.
+    ///
+    /// **Note:** Do not rely on the order in which the elements are returned.
+    pub fn iter(&self) -> Iter<'_, IT> {
+        self.keyed.inner.iter()
+    }
+
+    /// Returns the number of slots, which is the length of the buffer.
+    pub fn capacity(&self) -> usize {
+        self.keyed.inner.capacity()
+    }
+
+    /// Returns the number of occupied slots
+    pub fn count(&self) -> usize {
+        self.keyed.inner.count()
+    }
+
+    /// Returns whether all the slots are occupied and the next [`store()`](BorrowedSlots::store) will fail.
+    pub fn is_full(&self) -> bool {
+        self.keyed.inner.is_full()
+    }
+
+    /// Store an element in a free slot and return the key to access it.
+    ///
+    /// Storing a variable takes ownership over it. If the storage is full,
+    /// the inserted data is returned in the return value.
+    pub fn store(&mut self, item: IT) -> Result<Key<IT, I, O>, IT> {
+        self.keyed.store(item).map(Key::new)
+    }
+
+    /// Store an element in a free slot and return the key to access it.
+    ///
+    /// If the storage is full, the inserted data is returned in [`SlotsError::Full`].
+    pub fn try_store(&mut self, item: IT) -> Result<Key<IT, I, O>, SlotsError<IT>> {
+        self.store(item).map_err(SlotsError::Full)
+    }
+
+    /// Remove and return the element that belongs to the key.
+    pub fn take(&mut self, key: Key<IT, I, O>) -> IT {
+        self.keyed.take(key.raw)
+    }
+
+    /// Remove and return the element that belongs to the key.
+    ///
+    /// Unlike [`take`](BorrowedSlots::take), this method returns an error instead of panicking if
+    /// the key belongs to a different instance. If the element can't be taken,
+    /// the key is handed back in the [`TakeError`].
+    pub fn try_take(&mut self, key: Key<IT, I, O>) -> Result<IT, TakeError<Key<IT, I, O>, IT>> {
+        self.keyed
+            .try_take(key.raw)
+            .map_err(|error| error.map_key(Key::new))
+    }
+
+    /// Read the element that belongs to the key.
+    ///
+    /// This operation does not move ownership so the `function` callback must be used
+    /// to access the stored element. The callback may return arbitrary derivative of the element.
+    pub fn read<T>(&self, key: &Key<IT, I, O>, function: impl FnOnce(&IT) -> T) -> T {
+        function(self.get(key))
+    }
+
+    /// Read the element that belongs to a particular index. Since the index may point to
+    /// a free slot or outside the collection, this operation may return None without invoking the callback.
+    pub fn try_read<T>(&self, index: usize, function: impl FnOnce(&IT) -> T) -> Option<T> {
+        self.keyed.inner.read(index, function)
+    }
+
+    /// Read the element that belongs to the key.
+    ///
+    /// Unlike [`read`](BorrowedSlots::read), this method returns an error instead of panicking if
+    /// the key belongs to a different instance.
+    pub fn try_read_key<T>(
+        &self,
+        key: &Key<IT, I, O>,
+        function: impl FnOnce(&IT) -> T,
+    ) -> Result<T, SlotsError<IT>> {
+        self.keyed.try_get_key(&key.raw).map(function)
+    }
+
+    /// Access the element that belongs to the key for modification.
+    ///
+    /// This operation does not move ownership so the `function` callback must be used
+    /// to access the stored element. The callback may return arbitrary derivative of the element.
+    pub fn modify<T>(&mut self, key: &Key<IT, I, O>, function: impl FnOnce(&mut IT) -> T) -> T {
+        function(self.get_mut(key))
+    }
+
+    /// Access the element that belongs to the key for modification.
+    ///
+    /// Unlike [`modify`](BorrowedSlots::modify), this method returns an error instead of
+    /// panicking if the key belongs to a different instance.
+    pub fn try_modify<T>(
+        &mut self,
+        key: &Key<IT, I, O>,
+        function: impl FnOnce(&mut IT) -> T,
+    ) -> Result<T, SlotsError<IT>> {
+        self.keyed.try_get_key_mut(&key.raw).map(function)
+    }
+
+    /// Returns a reference to the element that belongs to the key.
+    pub fn get(&self, key: &Key<IT, I, O>) -> &IT {
+        self.keyed.get(&key.raw)
+    }
+
+    /// Returns a mutable reference to the element that belongs to the key.
+    pub fn get_mut(&mut self, key: &Key<IT, I, O>) -> &mut IT {
+        self.keyed.get_mut(&key.raw)
+    }
+
+    /// Returns mutable references to the elements that belong to multiple keys at once.
+    ///
+    /// Since keys can't be copied, every key refers to a different slot.
+    pub fn get_many_mut<const M: usize>(&mut self, keys: [&Key<IT, I, O>; M]) -> [&mut IT; M] {
+        self.keyed.get_many_mut(keys.map(|key| &key.raw))
+    }
+
+    /// Returns a reference to the element that belongs to a particular index.
+    ///
+    /// This operation is fallible. If `index` addresses a free slot or is outside the collection,
+    /// `None` is returned.
+    pub fn try_get(&self, index: usize) -> Option<&IT> {
+        self.keyed.inner.get(index)
+    }
+}
+
+impl<IT, I: SlotIndex, O: OwnerIdSource> Index<&Key<IT, I, O>> for BorrowedSlots<'_, IT, I, O> {
+    type Output = IT;
+
+    fn index(&self, key: &Key<IT, I, O>) -> &IT {
+        self.get(key)
+    }
+}
+
+impl<IT, I: SlotIndex, O: OwnerIdSource> IndexMut<&Key<IT, I, O>> for BorrowedSlots<'_, IT, I, O> {
+    fn index_mut(&mut self, key: &Key<IT, I, O>) -> &mut IT {
+        self.get_mut(key)
+    }
+}
+
+impl<'a, 'b, IT, I: SlotIndex> IntoIterator for &'a BorrowedUnrestrictedSlots<'b, IT, I> {
+    type Item = &'a IT;
+    type IntoIter = Iter<'a, IT>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, 'b, IT, I: SlotIndex> IntoIterator for &'a mut BorrowedUnrestrictedSlots<'b, IT, I> {
+    type Item = &'a mut IT;
+    type IntoIter = IterMut<'a, IT>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<'a, 'b, IT, I: SlotIndex, O: OwnerIdSource> IntoIterator for &'a BorrowedSlots<'b, IT, I, O> {
+    type Item = &'a IT;
+    type IntoIter = Iter<'a, IT>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
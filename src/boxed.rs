@@ -0,0 +1,299 @@
+//! Heap allocated Slots object with a capacity chosen at runtime.
+//!
+//! [`BoxSlots`] provides the same access control as [`Slots`]: elements can only be modified,
+//! or taken out, using the [`Key`] that was returned when they were stored. Unlike [`Slots`], the
+//! number of slots is not a type parameter. The slots are allocated on the heap when the
+//! collection is created, and are never reallocated, so every operation remains `O(1)`.
+//!
+//! This module is only available when the `alloc` feature is enabled.
+//!
+//! ```rust
+//! use slots::boxed::BoxSlots;
+//!
+//! // the capacity may come from a configuration file
+//! let capacity = 2;
+//! let mut slots: BoxSlots<u32> = BoxSlots::with_capacity(capacity);
+//!
+//! let k1 = slots.store(2).unwrap();
+//! let k2 = slots.store(4).unwrap();
+//!
+//! assert!(slots.is_full());
+//! assert_eq!(Err(8), slots.store(8).map(|_| ()));
+//!
+//! slots.modify(&k2, |e| *e += 1);
+//!
+//! assert_eq!(2, slots.take(k1));
+//! assert_eq!(5, slots[&k2]);
+//! ```
+//!
+//! [`Slots`]: crate::slots::Slots
+
+use core::marker::PhantomData;
+use core::ops::{Index, IndexMut};
+
+use crate::error::{SlotsError, TakeError};
+use crate::index::SlotIndex;
+use crate::iterator::Iter;
+use crate::keyed::{Keyed, RawKey};
+use crate::owner::{DefaultOwnerId, OwnerIdSource};
+use crate::raw::{BoxStorage, RawSlots};
+
+/// The key used to access stored elements.
+///
+/// **Important:** It should only be used to access the same collection that returned it.
+/// When the owner id strategy does not allocate ids (e.g. because the `runtime_checks` feature
+/// is disabled), extra care must be taken to ensure this constraint. See [`crate::owner`].
+///
+/// The index is stored as a non-zero value, so `Option<Key>` is the same size as `Key`.
+#[derive(Debug)]
+pub struct Key<IT, I: SlotIndex = usize, O: OwnerIdSource = DefaultOwnerId> {
+    raw: RawKey<I, O, ()>,
+    _item_marker: PhantomData<IT>,
+}
+
+impl<IT, I: SlotIndex, O: OwnerIdSource> Key<IT, I, O> {
+    fn new(raw: RawKey<I, O, ()>) -> Self {
+        Self {
+            raw,
+            _item_marker: PhantomData,
+        }
+    }
+
+    pub fn index(&self) -> usize {
+        self.raw.index()
+    }
+}
+
+/// Heap allocated Slots object with a capacity chosen at runtime.
+///
+/// The struct has three type parameters:
+///  - `IT` is the type of the stored data
+///  - `I` is the type used to store slot indices, see [`SlotIndex`]. Defaults to `usize`.
+///  - `O` is the strategy used to allocate owner ids, see [`OwnerIdSource`]. Defaults to [`DefaultOwnerId`].
+///
+/// For more information, see the [module level documentation](crate::boxed)
+pub struct BoxSlots<IT, I: SlotIndex = usize, O: OwnerIdSource = DefaultOwnerId> {
+    keyed: Keyed<RawSlots<BoxStorage<IT, I>>, (), O>,
+}
+
+impl<IT, I: SlotIndex, O: OwnerIdSource> BoxSlots<IT, I, O> {
+    /// Creates a new, empty BoxSlots object that can hold `capacity` elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` can not be represented by the index type.
+    ///
+    /// ```
+    /// # use slots::boxed::BoxSlots;
+    /// let slots: BoxSlots<u32, u8> = BoxSlots::with_capacity(200);
+    ///
+    /// assert_eq!(200, slots.capacity());
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        assert!(
+            capacity <= I::MAX_CAPACITY,
+            "The capacity can not be represented by the index type"
+        );
+
+        Self {
+            keyed: Keyed::new(RawSlots::new(BoxStorage::new(capacity)), ()),
+        }
+    }
+
+    /// Returns a read-only iterator.
+    /// The iterator can be used to read data from all occupied slots.
+    ///
+    /// **Note:** Do not rely on the order in which the elements are returned.
+    ///
+    /// ```
+    /// # use slots::boxed::BoxSlots;
+    /// # let mut slots: BoxSlots<_> = BoxSlots::with_capacity(4);
+    /// slots.store(2).unwrap();
+    /// slots.store(4).unwrap();
+    /// slots.store(6).unwrap();
+    ///
+    /// assert_eq!(true, slots.iter().any(|&x| x < 3));
+    /// ```
+    pub fn iter(&self) -> Iter<'_, IT> {
+        self.keyed.inner.iter()
+    }
+
+    /// Returns the number of slots
+    pub fn capacity(&self) -> usize {
+        self.keyed.inner.capacity()
+    }
+
+    /// Returns the number of occupied slots
+    ///
+    /// ```
+    /// # use slots::boxed::BoxSlots;
+    /// let mut slots: BoxSlots<_> = BoxSlots::with_capacity(4);
+    ///
+    /// assert_eq!(0, slots.count());
+    ///
+    /// slots.store(3).unwrap();
+    /// slots.store(6).unwrap();
+    ///
+    /// assert_eq!(2, slots.count());
+    /// ```
+    pub fn count(&self) -> usize {
+        self.keyed.inner.count()
+    }
+
+    /// Returns whether all the slots are occupied and the next [`store()`](BoxSlots::store) will fail.
+    pub fn is_full(&self) -> bool {
+        self.keyed.inner.is_full()
+    }
+
+    /// Store an element in a free slot and return the key to access it.
+    ///
+    /// Storing a variable takes ownership over it. If the storage is full,
+    /// the inserted data is returned in the return value.
+    pub fn store(&mut self, item: IT) -> Result<Key<IT, I, O>, IT> {
+        self.keyed.store(item).map(Key::new)
+    }
+
+    /// Store an element in a free slot and return the key to access it.
+    ///
+    /// If the storage is full, the inserted data is returned in [`SlotsError::Full`].
+    pub fn try_store(&mut self, item: IT) -> Result<Key<IT, I, O>, SlotsError<IT>> {
+        self.store(item).map_err(SlotsError::Full)
+    }
+
+    /// Remove and return the element that belongs to the key.
+    pub fn take(&mut self, key: Key<IT, I, O>) -> IT {
+        self.keyed.take(key.raw)
+    }
+
+    /// Remove and return the element that belongs to the key.
+    ///
+    /// Unlike [`take`](BoxSlots::take), this method returns an error instead of panicking if the
+    /// key belongs to a different instance. If the element can't be taken,
+    /// the key is handed back in the [`TakeError`].
+    pub fn try_take(&mut self, key: Key<IT, I, O>) -> Result<IT, TakeError<Key<IT, I, O>, IT>> {
+        self.keyed
+            .try_take(key.raw)
+            .map_err(|error| error.map_key(Key::new))
+    }
+
+    /// Read the element that belongs to the key.
+    ///
+    /// This operation does not move ownership so the `function` callback must be used
+    /// to access the stored element. The callback may return arbitrary derivative of the element.
+    ///
+    /// ```
+    /// # use slots::boxed::BoxSlots;
+    /// # let mut slots: BoxSlots<_> = BoxSlots::with_capacity(4);
+    ///
+    /// let k = slots.store(3).unwrap();
+    ///
+    /// assert_eq!(4, slots.read(&k, |elem| {
+    ///     elem + 1
+    /// }));
+    /// ```
+    pub fn read<T>(&self, key: &Key<IT, I, O>, function: impl FnOnce(&IT) -> T) -> T {
+        function(self.get(key))
+    }
+
+    /// Read the element that belongs to a particular index. Since the index may point to
+    /// a free slot or outside the collection, this operation may return None without invoking the callback.
+    pub fn try_read<T>(&self, index: usize, function: impl FnOnce(&IT) -> T) -> Option<T> {
+        self.keyed.inner.get(index).ok().map(function)
+    }
+
+    /// Read the element that belongs to the key.
+    ///
+    /// Unlike [`read`](BoxSlots::read), this method returns an error instead of panicking if the
+    /// key belongs to a different instance.
+    pub fn try_read_key<T>(
+        &self,
+        key: &Key<IT, I, O>,
+        function: impl FnOnce(&IT) -> T,
+    ) -> Result<T, SlotsError<IT>> {
+        self.keyed.try_get_key(&key.raw).map(function)
+    }
+
+    /// Access the element that belongs to the key for modification.
+    ///
+    /// This operation does not move ownership so the `function` callback must be used
+    /// to access the stored element. The callback may return arbitrary derivative of the element.
+    ///
+    /// ```
+    /// # use slots::boxed::BoxSlots;
+    /// # let mut slots: BoxSlots<_> = BoxSlots::with_capacity(4);
+    ///
+    /// let k = slots.store(3).unwrap();
+    ///
+    /// assert_eq!("found", slots.modify(&k, |elem| {
+    ///     *elem = *elem + 1;
+    ///
+    ///     "found"
+    /// }));
+    ///
+    /// // Assert that the stored data was modified
+    /// assert_eq!(4, slots.take(k));
+    /// ```
+    pub fn modify<T>(&mut self, key: &Key<IT, I, O>, function: impl FnOnce(&mut IT) -> T) -> T {
+        function(self.get_mut(key))
+    }
+
+    /// Access the element that belongs to the key for modification.
+    ///
+    /// Unlike [`modify`](BoxSlots::modify), this method returns an error instead of panicking if
+    /// the key belongs to a different instance.
+    pub fn try_modify<T>(
+        &mut self,
+        key: &Key<IT, I, O>,
+        function: impl FnOnce(&mut IT) -> T,
+    ) -> Result<T, SlotsError<IT>> {
+        self.keyed.try_get_key_mut(&key.raw).map(function)
+    }
+
+    /// Returns a reference to the element that belongs to the key.
+    pub fn get(&self, key: &Key<IT, I, O>) -> &IT {
+        self.keyed.get(&key.raw)
+    }
+
+    /// Returns a mutable reference to the element that belongs to the key.
+    pub fn get_mut(&mut self, key: &Key<IT, I, O>) -> &mut IT {
+        self.keyed.get_mut(&key.raw)
+    }
+
+    /// Returns mutable references to the elements that belong to multiple keys at once.
+    ///
+    /// Since keys can't be copied, every key refers to a different slot.
+    pub fn get_many_mut<const M: usize>(&mut self, keys: [&Key<IT, I, O>; M]) -> [&mut IT; M] {
+        self.keyed.get_many_mut(keys.map(|key| &key.raw))
+    }
+
+    /// Returns a reference to the element that belongs to a particular index.
+    ///
+    /// This operation is fallible. If `index` addresses a free slot or is outside the collection,
+    /// `None` is returned.
+    pub fn try_get(&self, index: usize) -> Option<&IT> {
+        self.keyed.inner.get(index).ok()
+    }
+}
+
+impl<IT, I: SlotIndex, O: OwnerIdSource> Index<&Key<IT, I, O>> for BoxSlots<IT, I, O> {
+    type Output = IT;
+
+    fn index(&self, key: &Key<IT, I, O>) -> &IT {
+        self.get(key)
+    }
+}
+
+impl<IT, I: SlotIndex, O: OwnerIdSource> IndexMut<&Key<IT, I, O>> for BoxSlots<IT, I, O> {
+    fn index_mut(&mut self, key: &Key<IT, I, O>) -> &mut IT {
+        self.get_mut(key)
+    }
+}
+
+impl<'a, IT, I: SlotIndex, O: OwnerIdSource> IntoIterator for &'a BoxSlots<IT, I, O> {
+    type Item = &'a IT;
+    type IntoIter = Iter<'a, IT>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
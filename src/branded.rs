@@ -0,0 +1,261 @@
+//! Slots object that verifies key ownership at compile time.
+//!
+//! [`BrandedSlots`] provides the same access control as [`Slots`], but instead of storing an
+//! owner id in every key and comparing it on every access, keys carry a lifetime _brand_ that is
+//! unique to the collection that created them. Using a key with a different collection is a
+//! compile error, so keys are as small as an index and accessing an element never has to
+//! verify its owner, even with the `runtime_checks` feature disabled.
+//!
+//! A collection can only be created using [`BrandedSlots::scope`], which passes a new, empty
+//! collection with a fresh brand to a closure. The collection and its keys can't leave the closure.
+//!
+//! ```rust
+//! use slots::branded::BrandedSlots;
+//!
+//! let sum = BrandedSlots::<_, 4>::scope(|mut slots| {
+//!     let k1 = slots.store(2).unwrap();
+//!     let k2 = slots.store(4).unwrap();
+//!
+//!     slots[&k1] += 1;
+//!
+//!     slots.take(k1) + slots.read(&k2, |&e| e)
+//! });
+//!
+//! assert_eq!(7, sum);
+//! ```
+//!
+//! Keys of one collection are rejected by every other collection:
+//!
+//! ```compile_fail
+//! use slots::branded::BrandedSlots;
+//!
+//! BrandedSlots::<u32, 4>::scope(|mut a| {
+//!     BrandedSlots::<u32, 4>::scope(|mut b| {
+//!         let key = a.store(5).unwrap();
+//!
+//!         b.take(key); // the brands of `a` and `b` are different
+//!     });
+//! });
+//! ```
+//!
+//! Keys can't be smuggled out of the scope either:
+//!
+//! ```compile_fail
+//! use slots::branded::BrandedSlots;
+//!
+//! let key = BrandedSlots::<u32, 4>::scope(|mut slots| slots.store(5).unwrap());
+//! ```
+//!
+//! [`Slots`]: crate::slots::Slots
+
+use core::marker::PhantomData;
+use core::ops::{Index, IndexMut};
+
+use crate::index::SlotIndex;
+use crate::iterator::Iter;
+use crate::unrestricted::UnrestrictedSlots;
+
+/// Marker that makes `'brand` invariant, so different brands can never be unified.
+type Brand<'brand> = PhantomData<fn(&'brand ()) -> &'brand ()>;
+
+/// The key used to access stored elements.
+///
+/// The key can only be used with the collection that returned it, which is verified at compile
+/// time. `Option<Key>` is the same size as `Key`, which is the same size as the index type.
+#[derive(Debug)]
+pub struct Key<'brand, IT, const N: usize, I: SlotIndex = usize> {
+    index: I::NonZero,
+    _brand: Brand<'brand>,
+    _item_marker: PhantomData<IT>,
+}
+
+impl<IT, const N: usize, I: SlotIndex> Key<'_, IT, N, I> {
+    fn new(idx: usize) -> Self {
+        Self {
+            index: I::to_non_zero(idx),
+            _brand: PhantomData,
+            _item_marker: PhantomData,
+        }
+    }
+
+    pub fn index(&self) -> usize {
+        I::from_non_zero(self.index)
+    }
+}
+
+/// Slots object that verifies key ownership at compile time.
+///
+/// The struct has four parameters:
+///  - `'brand` is the unique lifetime that ties keys to this collection
+///  - `IT` is the type of the stored data
+///  - `N` is the number of slots.
+///  - `I` is the type used to store slot indices, see [`SlotIndex`]. Defaults to `usize`.
+///
+/// For more information, see the [module level documentation](crate::branded)
+pub struct BrandedSlots<'brand, IT, const N: usize, I: SlotIndex = usize> {
+    inner: UnrestrictedSlots<IT, N, I>,
+    _brand: Brand<'brand>,
+}
+
+impl<IT, const N: usize, I: SlotIndex> BrandedSlots<'_, IT, N, I> {
+    /// Creates a new, empty collection with a unique brand and passes it to `function`.
+    ///
+    /// The value returned by `function` is returned by this method.
+    ///
+    /// ```
+    /// # use slots::branded::BrandedSlots;
+    /// let taken = BrandedSlots::<_, 4>::scope(|mut slots| {
+    ///     let k = slots.store(3).unwrap();
+    ///     slots.take(k)
+    /// });
+    ///
+    /// assert_eq!(3, taken);
+    /// ```
+    pub fn scope<T>(function: impl for<'new> FnOnce(BrandedSlots<'new, IT, N, I>) -> T) -> T {
+        function(BrandedSlots {
+            inner: UnrestrictedSlots::new(),
+            _brand: PhantomData,
+        })
+    }
+}
+
+impl<'brand, IT, const N: usize, I: SlotIndex> BrandedSlots<'brand, IT, N, I> {
+    /// Returns a read-only iterator.
+    /// The iterator can be used to read data from all occupied slots.
+    ///
+    /// **Note:** Do not rely on the order in which the elements are returned.
+    ///
+    /// ```
+    /// # use slots::branded::BrandedSlots;
+    /// BrandedSlots::<_, 4>::scope(|mut slots| {
+    ///     slots.store(2).unwrap();
+    ///     slots.store(4).unwrap();
+    ///     slots.store(6).unwrap();
+    ///
+    ///     assert_eq!(true, slots.iter().any(|&x| x < 3));
+    /// });
+    /// ```
+    pub fn iter(&self) -> Iter<'_, IT> {
+        self.inner.iter()
+    }
+
+    /// Returns the number of slots
+    ///
+    /// ```
+    /// # use slots::branded::BrandedSlots;
+    /// BrandedSlots::<f32, 4>::scope(|slots| {
+    ///     assert_eq!(4, slots.capacity());
+    /// });
+    /// ```
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns the number of occupied slots
+    ///
+    /// ```
+    /// # use slots::branded::BrandedSlots;
+    /// BrandedSlots::<_, 4>::scope(|mut slots| {
+    ///     assert_eq!(0, slots.count());
+    ///
+    ///     slots.store(3).unwrap();
+    ///     slots.store(6).unwrap();
+    ///
+    ///     assert_eq!(2, slots.count());
+    /// });
+    /// ```
+    pub fn count(&self) -> usize {
+        self.inner.count()
+    }
+
+    /// Returns whether all the slots are occupied and the next [`store()`](BrandedSlots::store) will fail.
+    pub fn is_full(&self) -> bool {
+        self.inner.is_full()
+    }
+
+    /// Store an element in a free slot and return the key to access it.
+    ///
+    /// Storing a variable takes ownership over it. If the storage is full,
+    /// the inserted data is returned in the return value.
+    pub fn store(&mut self, item: IT) -> Result<Key<'brand, IT, N, I>, IT> {
+        self.inner.store(item).map(Key::new)
+    }
+
+    /// Remove and return the element that belongs to the key.
+    pub fn take(&mut self, key: Key<'brand, IT, N, I>) -> IT {
+        self.inner.take(key.index()).expect("Invalid key")
+    }
+
+    /// Read the element that belongs to the key.
+    ///
+    /// This operation does not move ownership so the `function` callback must be used
+    /// to access the stored element. The callback may return arbitrary derivative of the element.
+    pub fn read<T>(&self, key: &Key<'brand, IT, N, I>, function: impl FnOnce(&IT) -> T) -> T {
+        function(self.get(key))
+    }
+
+    /// Read the element that belongs to a particular index. Since the index may point to
+    /// a free slot or outside the collection, this operation may return None without invoking the callback.
+    pub fn try_read<T>(&self, index: usize, function: impl FnOnce(&IT) -> T) -> Option<T> {
+        self.inner.read(index, function)
+    }
+
+    /// Access the element that belongs to the key for modification.
+    ///
+    /// This operation does not move ownership so the `function` callback must be used
+    /// to access the stored element. The callback may return arbitrary derivative of the element.
+    pub fn modify<T>(
+        &mut self,
+        key: &Key<'brand, IT, N, I>,
+        function: impl FnOnce(&mut IT) -> T,
+    ) -> T {
+        function(self.get_mut(key))
+    }
+
+    /// Returns a reference to the element that belongs to the key.
+    pub fn get(&self, key: &Key<'brand, IT, N, I>) -> &IT {
+        self.inner.get(key.index()).expect("Invalid key")
+    }
+
+    /// Returns a mutable reference to the element that belongs to the key.
+    pub fn get_mut(&mut self, key: &Key<'brand, IT, N, I>) -> &mut IT {
+        self.inner.get_mut(key.index()).expect("Invalid key")
+    }
+
+    /// Returns a reference to the element that belongs to a particular index.
+    ///
+    /// This operation is fallible. If `index` addresses a free slot or is outside the collection,
+    /// `None` is returned.
+    pub fn try_get(&self, index: usize) -> Option<&IT> {
+        self.inner.get(index)
+    }
+}
+
+impl<'brand, IT, const N: usize, I: SlotIndex> Index<&Key<'brand, IT, N, I>>
+    for BrandedSlots<'brand, IT, N, I>
+{
+    type Output = IT;
+
+    fn index(&self, key: &Key<'brand, IT, N, I>) -> &IT {
+        self.get(key)
+    }
+}
+
+impl<'brand, IT, const N: usize, I: SlotIndex> IndexMut<&Key<'brand, IT, N, I>>
+    for BrandedSlots<'brand, IT, N, I>
+{
+    fn index_mut(&mut self, key: &Key<'brand, IT, N, I>) -> &mut IT {
+        self.get_mut(key)
+    }
+}
+
+impl<'a, 'brand, IT, const N: usize, I: SlotIndex> IntoIterator
+    for &'a BrandedSlots<'brand, IT, N, I>
+{
+    type Item = &'a IT;
+    type IntoIter = Iter<'a, IT>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
@@ -0,0 +1,308 @@
+//! Slots object that can be shared between threads.
+//!
+//! Data type that stores values and returns a key that can be used to manipulate
+//! the stored values. Like [`Slots`], values can only be accessed using the key that
+//! was returned when the value was stored.
+//!
+//! Unlike [`Slots`], storing and taking elements only requires a shared reference to the
+//! collection, so a single instance can be used by multiple producers and consumers without
+//! a lock. Free slots are kept in a lock-free stack. The top of the stack is tagged with a
+//! counter that changes with every update, which protects against the [ABA problem][aba-problem].
+//!
+//! ```rust
+//! use slots::concurrent::ConcurrentSlots;
+//! use std::sync::Arc;
+//!
+//! let slots: Arc<ConcurrentSlots<u32, 4>> = Arc::new(ConcurrentSlots::new());
+//!
+//! let producer = {
+//!     let slots = slots.clone();
+//!     std::thread::spawn(move || slots.store(5).unwrap())
+//! };
+//!
+//! let key = producer.join().unwrap();
+//!
+//! assert_eq!(5, slots.take(key));
+//! ```
+//!
+//! `ConcurrentSlots` returns the same [`Key`] type as a `Slots` object that uses
+//! [`AtomicOwnerId`]. Keys always carry the id of their owner, regardless of the
+//! `runtime_checks` feature, because using a key with the wrong instance could otherwise
+//! cause a data race.
+//!
+//! The index and the tag share a single `usize`. On 64-bit targets, both take 32 bits. On 32-bit
+//! targets, the capacity is limited to 65535 slots and the tag wraps around after 65536 updates
+//! of the free stack. A thread that is suspended between reading the top of the stack and
+//! replacing it while other threads store and take exactly a multiple of 65536 elements could
+//! then mistake the changed stack for the one it read, which is the ABA problem the tag is meant
+//! to prevent. Such a window is unlikely, but it is not ruled out on 32-bit targets.
+//!
+//! This module is only available on targets that support atomic compare-and-swap operations.
+//!
+//! [`Slots`]: crate::slots
+//! [`Key`]: crate::slots::Key
+//! [`AtomicOwnerId`]: crate::owner::AtomicOwnerId
+//! [aba-problem]: https://en.wikipedia.org/wiki/ABA_problem
+
+use core::mem::MaybeUninit;
+
+use crate::keyed::RawKey;
+use crate::owner::{AtomicOwnerId, OwnerIdSource};
+use crate::sync::{AtomicUsize, Ordering, UnsafeCell};
+
+/// Number of bits used to store the slot index in the tagged top of the free stack.
+/// The remaining bits hold the tag.
+const INDEX_BITS: u32 = usize::BITS / 2;
+const INDEX_MASK: usize = (1 << INDEX_BITS) - 1;
+
+/// Marks the free stack link of occupied slots.
+const OCCUPIED: usize = usize::MAX;
+
+/// Replaces the index part of a tagged stack top and increments the tag.
+fn retag(head: usize, top: usize) -> usize {
+    ((head >> INDEX_BITS).wrapping_add(1) << INDEX_BITS) | top
+}
+
+/// The key used to access stored elements. The same type as the key of a
+/// [`Slots`](crate::slots::Slots) object that uses [`AtomicOwnerId`].
+///
+/// **Important:** It can only be used to access the same collection that returned it.
+pub type Key<IT, const N: usize> = crate::slots::Key<IT, N, usize, AtomicOwnerId>;
+
+/// Slots object that can be shared between threads.
+///
+/// The struct has two type parameters:
+///  - `IT` is the type of the stored data
+///  - `N` is the number of slots. On 32-bit targets, at most 65535, see the
+///    [module level documentation](crate::concurrent) for the limits of the tagged free stack.
+///
+/// For more information, see the [module level documentation](crate::concurrent)
+pub struct ConcurrentSlots<IT, const N: usize> {
+    id: usize,
+    items: [UnsafeCell<MaybeUninit<IT>>; N],
+    /// Free stack links, stored as `index + 1` with zero marking the bottom of the stack.
+    next: [AtomicUsize; N],
+    /// Tagged top of the free stack: the index part is stored as `index + 1`, zero if the stack
+    /// is empty.
+    head: AtomicUsize,
+    count: AtomicUsize,
+}
+
+// Safety: stored elements may be taken out on a different thread than the one that stored them.
+unsafe impl<IT: Send, const N: usize> Send for ConcurrentSlots<IT, N> {}
+
+// Safety: elements are only accessed by the holder of their key. Elements can be read through
+// a shared key reference, which may be used on multiple threads at once.
+unsafe impl<IT: Send + Sync, const N: usize> Sync for ConcurrentSlots<IT, N> {}
+
+impl<IT, const N: usize> Default for ConcurrentSlots<IT, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<IT, const N: usize> ConcurrentSlots<IT, N> {
+    const CAPACITY_FITS_INDEX: () = assert!(
+        N <= INDEX_MASK,
+        "The capacity can not be represented in a tagged index on this target"
+    );
+
+    /// Creates a new, empty ConcurrentSlots object.
+    pub fn new() -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::CAPACITY_FITS_INDEX;
+
+        Self {
+            id: AtomicOwnerId::next_id(),
+            items: core::array::from_fn(|_| UnsafeCell::new(MaybeUninit::uninit())),
+            // every slot links to the one below it
+            next: core::array::from_fn(AtomicUsize::new),
+            head: AtomicUsize::new(N),
+            count: AtomicUsize::new(0),
+        }
+    }
+
+    /// Returns the number of slots
+    ///
+    /// ```
+    /// # use slots::concurrent::ConcurrentSlots;
+    /// let slots: ConcurrentSlots<f32, 4> = ConcurrentSlots::new();
+    ///
+    /// assert_eq!(4, slots.capacity());
+    /// ```
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns the number of occupied slots
+    ///
+    /// **Note:** other threads may store or take elements at any time, so the returned
+    /// value may already be outdated.
+    ///
+    /// ```
+    /// # use slots::concurrent::ConcurrentSlots;
+    /// let slots: ConcurrentSlots<_, 4> = ConcurrentSlots::new();
+    ///
+    /// assert_eq!(0, slots.count());
+    ///
+    /// slots.store(3).unwrap();
+    /// slots.store(6).unwrap();
+    ///
+    /// assert_eq!(2, slots.count());
+    /// ```
+    pub fn count(&self) -> usize {
+        self.count.load(Ordering::Relaxed)
+    }
+
+    /// Returns whether all the slots are occupied and the next [`store()`](ConcurrentSlots::store) will fail.
+    ///
+    /// **Note:** other threads may store or take elements at any time, so the returned
+    /// value may already be outdated.
+    pub fn is_full(&self) -> bool {
+        self.count() == self.capacity()
+    }
+
+    fn verify_key(&self, key: &Key<IT, N>) {
+        assert_eq!(key.raw().owner_id(), self.id, "Key used in wrong instance");
+    }
+
+    fn alloc(&self) -> Option<usize> {
+        let mut head = self.head.load(Ordering::Acquire);
+        loop {
+            let top = head & INDEX_MASK;
+            if top == 0 {
+                // no free slot
+                return None;
+            }
+
+            let index = top - 1;
+            // If another thread pops this slot in the meantime, the link may be outdated,
+            // but the tag of the top will also change, so the exchange fails.
+            let next = self.next[index].load(Ordering::Relaxed);
+
+            match self.head.compare_exchange_weak(
+                head,
+                retag(head, next),
+                Ordering::Acquire,
+                Ordering::Acquire,
+            ) {
+                Ok(_) => {
+                    self.next[index].store(OCCUPIED, Ordering::Relaxed);
+                    self.count.fetch_add(1, Ordering::Relaxed);
+                    return Some(index);
+                }
+                Err(current) => head = current,
+            }
+        }
+    }
+
+    fn free(&self, index: usize) {
+        self.count.fetch_sub(1, Ordering::Relaxed);
+
+        let mut head = self.head.load(Ordering::Relaxed);
+        loop {
+            self.next[index].store(head & INDEX_MASK, Ordering::Relaxed);
+
+            match self.head.compare_exchange_weak(
+                head,
+                retag(head, index + 1),
+                Ordering::Release,
+                Ordering::Relaxed,
+            ) {
+                Ok(_) => return,
+                Err(current) => head = current,
+            }
+        }
+    }
+
+    /// Store an element in a free slot and return the key to access it.
+    ///
+    /// Storing a variable takes ownership over it. If the storage is full,
+    /// the inserted data is returned in the return value.
+    pub fn store(&self, item: IT) -> Result<Key<IT, N>, IT> {
+        match self.alloc() {
+            Some(index) => {
+                // Safety: the slot was just removed from the free stack, nobody else can access it
+                self.items[index].with_mut(|slot| unsafe { (*slot).write(item) });
+                // there is no clear, so slots don't need generations
+                Ok(Key::new(RawKey::new(self.id, 0, index)))
+            }
+            None => Err(item),
+        }
+    }
+
+    /// Remove and return the element that belongs to the key.
+    pub fn take(&self, key: Key<IT, N>) -> IT {
+        self.verify_key(&key);
+
+        let index = key.index();
+        // Safety: the slot is occupied and the key is consumed, so nobody else can access it
+        let item = self.items[index].with(|slot| unsafe { (*slot).assume_init_read() });
+        self.free(index);
+
+        item
+    }
+
+    /// Read the element that belongs to the key.
+    ///
+    /// This operation does not move ownership so the `function` callback must be used
+    /// to access the stored element. The callback may return arbitrary derivative of the element.
+    ///
+    /// ```
+    /// # use slots::concurrent::ConcurrentSlots;
+    /// # let slots: ConcurrentSlots<_, 4> = ConcurrentSlots::new();
+    ///
+    /// let k = slots.store(3).unwrap();
+    ///
+    /// assert_eq!(4, slots.read(&k, |elem| {
+    ///     elem + 1
+    /// }));
+    /// ```
+    pub fn read<T>(&self, key: &Key<IT, N>, function: impl FnOnce(&IT) -> T) -> T {
+        self.verify_key(key);
+
+        // Safety: the slot is occupied and can't be modified while the key is borrowed
+        self.items[key.index()].with(|slot| function(unsafe { (*slot).assume_init_ref() }))
+    }
+
+    /// Access the element that belongs to the key for modification.
+    ///
+    /// This operation does not move ownership so the `function` callback must be used
+    /// to access the stored element. The callback may return arbitrary derivative of the element.
+    ///
+    /// Because elements can be read by anyone holding a reference to their key, modifying
+    /// an element requires exclusive access to the key.
+    ///
+    /// ```
+    /// # use slots::concurrent::ConcurrentSlots;
+    /// # let slots: ConcurrentSlots<_, 4> = ConcurrentSlots::new();
+    ///
+    /// let mut k = slots.store(3).unwrap();
+    ///
+    /// assert_eq!("found", slots.modify(&mut k, |elem| {
+    ///     *elem = *elem + 1;
+    ///
+    ///     "found"
+    /// }));
+    ///
+    /// // Assert that the stored data was modified
+    /// assert_eq!(4, slots.take(k));
+    /// ```
+    pub fn modify<T>(&self, key: &mut Key<IT, N>, function: impl FnOnce(&mut IT) -> T) -> T {
+        self.verify_key(key);
+
+        // Safety: the slot is occupied and the key is borrowed exclusively
+        self.items[key.index()].with_mut(|slot| function(unsafe { (*slot).assume_init_mut() }))
+    }
+}
+
+impl<IT, const N: usize> Drop for ConcurrentSlots<IT, N> {
+    fn drop(&mut self) {
+        for (item, next) in self.items.iter().zip(self.next.iter()) {
+            if next.load(Ordering::Relaxed) == OCCUPIED {
+                // Safety: the slot is occupied and the collection is no longer accessible
+                item.with_mut(|slot| unsafe { (*slot).assume_init_drop() });
+            }
+        }
+    }
+}
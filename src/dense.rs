@@ -0,0 +1,359 @@
+//! Slots object that keeps the stored elements in a contiguous array.
+//!
+//! [`DenseSlots`] is addressed the same way as [`UnrestrictedSlots`]: storing an element returns
+//! a `usize` index that can be freely copied, and remains valid until the element is taken out.
+//! Unlike in other collections, the index does not address the element directly. The elements
+//! are kept at the start of an array without holes, and a table maps every index to the position
+//! of its element. Taking an element out moves the last element into its place.
+//!
+//! This makes iterating over every element as fast as iterating over a slice, at the cost of an
+//! extra indirection when accessing a single element. Every operation remains `O(1)`.
+//!
+//! ```rust
+//! use slots::dense::DenseSlots;
+//!
+//! let mut slots: DenseSlots<_, 4> = DenseSlots::new();
+//!
+//! let k1 = slots.store(2).unwrap();
+//! let k2 = slots.store(4).unwrap();
+//! let k3 = slots.store(6).unwrap();
+//!
+//! assert_eq!(&[2, 4, 6], slots.as_slice());
+//!
+//! // the last element fills the hole
+//! assert_eq!(Some(2), slots.take(k1));
+//! assert_eq!(&[6, 4], slots.as_slice());
+//!
+//! // indices are not affected
+//! assert_eq!(Some(&4), slots.get(k2));
+//! assert_eq!(Some(&6), slots.get(k3));
+//! ```
+//!
+//! **Note:** Do not rely on the order of the elements, it changes when elements are taken out.
+//!
+//! [`UnrestrictedSlots`]: crate::unrestricted::UnrestrictedSlots
+
+use core::mem::MaybeUninit;
+use core::ptr;
+use core::slice;
+
+use crate::error::SlotsError;
+use crate::index::SlotIndex;
+use crate::raw::{ArrayStorage, RawSlots};
+
+/// Converts an error of the position table.
+fn lookup_error<IT, I>(error: SlotsError<I>) -> SlotsError<IT> {
+    error.map_item(|_| unreachable!("Lookups never report a full collection"))
+}
+
+/// Slots object that keeps the stored elements in a contiguous array.
+///
+/// The struct has three type parameters:
+///  - `IT` is the type of the stored data
+///  - `N` is the number of slots.
+///  - `I` is the type used to store slot indices, see [`SlotIndex`]. Defaults to `usize`.
+///
+/// For more information, see the [module level documentation](crate::dense)
+pub struct DenseSlots<IT, const N: usize, I: SlotIndex = usize> {
+    /// Position of the element of every occupied slot.
+    positions: RawSlots<ArrayStorage<I, N, I>>,
+    /// The first `count` values are initialized.
+    values: [MaybeUninit<IT>; N],
+    /// The slot of every initialized value.
+    slots: [I; N],
+}
+
+impl<IT, const N: usize, I: SlotIndex> Default for DenseSlots<IT, N, I> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<IT, const N: usize, I: SlotIndex> DenseSlots<IT, N, I> {
+    const CAPACITY_FITS_INDEX: () = assert!(
+        N <= I::MAX_CAPACITY,
+        "The capacity can not be represented by the index type"
+    );
+
+    /// Creates a new, empty DenseSlots object.
+    ///
+    /// ```
+    /// # use slots::dense::DenseSlots;
+    /// static SLOTS: DenseSlots<u32, 16> = DenseSlots::new();
+    ///
+    /// assert_eq!(0, SLOTS.count());
+    /// ```
+    pub const fn new() -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::CAPACITY_FITS_INDEX;
+
+        Self {
+            positions: RawSlots::new(ArrayStorage::new()),
+            values: [const { MaybeUninit::uninit() }; N],
+            slots: [I::ZERO; N],
+        }
+    }
+
+    /// Returns the stored elements as a slice.
+    ///
+    /// **Note:** Do not rely on the order in which the elements are returned.
+    ///
+    /// ```
+    /// # use slots::dense::DenseSlots;
+    /// let mut slots: DenseSlots<_, 4> = DenseSlots::new();
+    /// slots.store(2).unwrap();
+    /// slots.store(4).unwrap();
+    ///
+    /// assert_eq!(6, slots.as_slice().iter().sum::<i32>());
+    /// ```
+    pub fn as_slice(&self) -> &[IT] {
+        // Safety: the first `count` values are initialized
+        unsafe { slice::from_raw_parts(self.values.as_ptr().cast::<IT>(), self.count()) }
+    }
+
+    /// Returns the stored elements as a mutable slice.
+    ///
+    /// Elements can be modified, but not removed, using the returned slice. Reordering the
+    /// elements, for example by sorting the slice, does not update the indices.
+    ///
+    /// **Note:** Do not rely on the order in which the elements are returned.
+    ///
+    /// ```
+    /// # use slots::dense::DenseSlots;
+    /// let mut slots: DenseSlots<_, 4> = DenseSlots::new();
+    /// let k = slots.store(2).unwrap();
+    ///
+    /// for x in slots.as_mut_slice() {
+    ///     *x *= 2;
+    /// }
+    ///
+    /// assert_eq!(Some(4), slots.take(k));
+    /// ```
+    pub fn as_mut_slice(&mut self) -> &mut [IT] {
+        // Safety: the first `count` values are initialized
+        unsafe { slice::from_raw_parts_mut(self.values.as_mut_ptr().cast::<IT>(), self.count()) }
+    }
+
+    /// Returns a read-only iterator.
+    /// Equivalent to `as_slice().iter()`.
+    pub fn iter(&self) -> slice::Iter<'_, IT> {
+        self.as_slice().iter()
+    }
+
+    /// Returns a read-write iterator.
+    /// Equivalent to `as_mut_slice().iter_mut()`.
+    pub fn iter_mut(&mut self) -> slice::IterMut<'_, IT> {
+        self.as_mut_slice().iter_mut()
+    }
+
+    /// Returns the index of the element at `position` in [`as_slice`](DenseSlots::as_slice).
+    ///
+    /// ```
+    /// # use slots::dense::DenseSlots;
+    /// let mut slots: DenseSlots<_, 4> = DenseSlots::new();
+    /// let k = slots.store(2).unwrap();
+    ///
+    /// assert_eq!(Some(k), slots.index_at(0));
+    /// assert_eq!(None, slots.index_at(1));
+    /// ```
+    pub fn index_at(&self, position: usize) -> Option<usize> {
+        self.slots[..self.count()]
+            .get(position)
+            .map(|slot| slot.to_usize())
+    }
+
+    /// Returns the number of slots
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns the number of occupied slots
+    ///
+    /// ```
+    /// # use slots::dense::DenseSlots;
+    /// let mut slots: DenseSlots<_, 4> = DenseSlots::new();
+    ///
+    /// assert_eq!(0, slots.count());
+    ///
+    /// slots.store(3).unwrap();
+    /// slots.store(6).unwrap();
+    ///
+    /// assert_eq!(2, slots.count());
+    /// ```
+    pub fn count(&self) -> usize {
+        self.positions.count()
+    }
+
+    /// Returns whether all the slots are occupied and the next [`store()`](DenseSlots::store) will fail.
+    pub fn is_full(&self) -> bool {
+        self.positions.is_full()
+    }
+
+    /// Store an element in a free slot and return the key to access it.
+    ///
+    /// Storing a variable takes ownership over it. If the storage is full,
+    /// the inserted data is returned in the return value.
+    pub fn store(&mut self, item: IT) -> Result<usize, IT> {
+        let position = self.count();
+
+        match self.positions.store(I::from_usize(position)) {
+            Ok(key) => {
+                self.values[position].write(item);
+                self.slots[position] = I::from_usize(key);
+
+                Ok(key)
+            }
+            Err(_) => Err(item),
+        }
+    }
+
+    /// Store an element in a free slot and return the key to access it.
+    ///
+    /// If the storage is full, the inserted data is returned in [`SlotsError::Full`].
+    pub fn try_store(&mut self, item: IT) -> Result<usize, SlotsError<IT>> {
+        self.store(item).map_err(SlotsError::Full)
+    }
+
+    /// Remove and return the element that belongs to the key.
+    ///
+    /// The last element of [`as_slice`](DenseSlots::as_slice) is moved into the place of the
+    /// removed element.
+    ///
+    /// This operation is fallible. If `key` addresses a free slot or is outside the collection,
+    /// `None` is returned.
+    pub fn take(&mut self, key: usize) -> Option<IT> {
+        self.try_take(key).ok()
+    }
+
+    /// Remove and return the element that belongs to the key.
+    ///
+    /// This operation is fallible. If `key` addresses a free slot, [`SlotsError::Vacant`] is
+    /// returned. If `key` is outside the collection, [`SlotsError::OutOfBounds`] is returned.
+    ///
+    /// ```
+    /// # use slots::error::SlotsError;
+    /// # use slots::dense::DenseSlots;
+    /// # let mut slots: DenseSlots<_, 4> = DenseSlots::new();
+    /// let k = slots.store(3).unwrap();
+    ///
+    /// assert_eq!(Ok(3), slots.try_take(k));
+    /// assert_eq!(Err(SlotsError::Vacant), slots.try_take(k));
+    /// ```
+    pub fn try_take(&mut self, key: usize) -> Result<IT, SlotsError<IT>> {
+        let position = self.positions.take(key).map_err(lookup_error)?.to_usize();
+        let last = self.count();
+
+        // move the removed element to the end, so the initialized values stay contiguous
+        if position != last {
+            self.values.swap(position, last);
+            self.slots.swap(position, last);
+
+            let moved = self.slots[position].to_usize();
+            *self
+                .positions
+                .get_mut(moved)
+                .expect("Moved element has no slot") = I::from_usize(position);
+        }
+
+        // Safety: the value was initialized, and it is no longer counted as such
+        Ok(unsafe { self.values[last].assume_init_read() })
+    }
+
+    /// Read the element that belongs to a particular index. Since the index may point to
+    /// a free slot or outside the collection, this operation may return None without invoking the callback.
+    ///
+    /// ```
+    /// # use slots::dense::DenseSlots;
+    /// # let mut slots: DenseSlots<_, 4> = DenseSlots::new();
+    /// let k = slots.store(3).unwrap();
+    ///
+    /// assert_eq!(Some(4), slots.read(k, |elem| elem + 1));
+    /// ```
+    pub fn read<T>(&self, key: usize, function: impl FnOnce(&IT) -> T) -> Option<T> {
+        self.get(key).map(function)
+    }
+
+    /// Read the element that belongs to a particular index.
+    ///
+    /// Unlike [`read`](DenseSlots::read), this method returns the reason of the failure.
+    pub fn try_read<T>(
+        &self,
+        key: usize,
+        function: impl FnOnce(&IT) -> T,
+    ) -> Result<T, SlotsError<IT>> {
+        self.try_get(key).map(function)
+    }
+
+    /// Access the element that belongs to a particular index for modification. Since the index
+    /// may point to a free slot or outside the collection, this operation may return None without
+    /// invoking the callback.
+    pub fn modify<T>(&mut self, key: usize, function: impl FnOnce(&mut IT) -> T) -> Option<T> {
+        self.get_mut(key).map(function)
+    }
+
+    /// Access the element that belongs to a particular index for modification.
+    ///
+    /// Unlike [`modify`](DenseSlots::modify), this method returns the reason of the failure.
+    pub fn try_modify<T>(
+        &mut self,
+        key: usize,
+        function: impl FnOnce(&mut IT) -> T,
+    ) -> Result<T, SlotsError<IT>> {
+        self.try_get_mut(key).map(function)
+    }
+
+    fn try_get(&self, key: usize) -> Result<&IT, SlotsError<IT>> {
+        let position = self.positions.get(key).map_err(lookup_error)?.to_usize();
+
+        // Safety: positions of occupied slots point to initialized values
+        Ok(unsafe { self.values[position].assume_init_ref() })
+    }
+
+    fn try_get_mut(&mut self, key: usize) -> Result<&mut IT, SlotsError<IT>> {
+        let position = self.positions.get(key).map_err(lookup_error)?.to_usize();
+
+        // Safety: positions of occupied slots point to initialized values
+        Ok(unsafe { self.values[position].assume_init_mut() })
+    }
+
+    /// Returns a reference to the element that belongs to a particular index.
+    ///
+    /// This operation is fallible. If `key` addresses a free slot or is outside the collection,
+    /// `None` is returned.
+    pub fn get(&self, key: usize) -> Option<&IT> {
+        self.try_get(key).ok()
+    }
+
+    /// Returns a mutable reference to the element that belongs to a particular index.
+    ///
+    /// This operation is fallible. If `key` addresses a free slot or is outside the collection,
+    /// `None` is returned.
+    pub fn get_mut(&mut self, key: usize) -> Option<&mut IT> {
+        self.try_get_mut(key).ok()
+    }
+}
+
+impl<IT, const N: usize, I: SlotIndex> Drop for DenseSlots<IT, N, I> {
+    fn drop(&mut self) {
+        // Safety: the slice holds the initialized values, which are not used again
+        unsafe { ptr::drop_in_place(self.as_mut_slice()) }
+    }
+}
+
+impl<'a, IT, const N: usize, I: SlotIndex> IntoIterator for &'a DenseSlots<IT, N, I> {
+    type Item = &'a IT;
+    type IntoIter = slice::Iter<'a, IT>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, IT, const N: usize, I: SlotIndex> IntoIterator for &'a mut DenseSlots<IT, N, I> {
+    type Item = &'a mut IT;
+    type IntoIter = slice::IterMut<'a, IT>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
@@ -0,0 +1,115 @@
+//! Errors returned by the non-panicking methods of the collections.
+//!
+//! ```rust
+//! use slots::error::SlotsError;
+//! use slots::unrestricted::UnrestrictedSlots;
+//!
+//! let mut slots: UnrestrictedSlots<_, 1> = UnrestrictedSlots::new();
+//!
+//! let k = slots.try_store(3).unwrap();
+//!
+//! assert_eq!(Err(SlotsError::Full(4)), slots.try_store(4));
+//! assert_eq!(Ok(3), slots.try_take(k));
+//! assert_eq!(Err(SlotsError::Vacant), slots.try_take(k));
+//! assert_eq!(Err(SlotsError::OutOfBounds), slots.try_take(1));
+//! ```
+
+use core::fmt::{self, Debug, Display};
+
+/// The reason why an operation on a collection failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SlotsError<IT> {
+    /// The key was returned by a different collection.
+    WrongOwner,
+    /// The addressed slot does not hold an element.
+    Vacant,
+    /// The index is outside the collection.
+    OutOfBounds,
+    /// The collection is full. Holds the element that could not be stored.
+    Full(IT),
+}
+
+impl<IT> SlotsError<IT> {
+    /// Returns the element that could not be stored, if any.
+    ///
+    /// ```
+    /// # use slots::error::SlotsError;
+    /// assert_eq!(Some(5), SlotsError::Full(5).into_item());
+    /// assert_eq!(None, SlotsError::<i32>::Vacant.into_item());
+    /// ```
+    pub fn into_item(self) -> Option<IT> {
+        match self {
+            SlotsError::Full(item) => Some(item),
+            _ => None,
+        }
+    }
+
+    /// Converts the element that could not be stored, keeping the reason of the failure.
+    pub(crate) fn map_item<U>(self, function: impl FnOnce(IT) -> U) -> SlotsError<U> {
+        match self {
+            SlotsError::WrongOwner => SlotsError::WrongOwner,
+            SlotsError::Vacant => SlotsError::Vacant,
+            SlotsError::OutOfBounds => SlotsError::OutOfBounds,
+            SlotsError::Full(item) => SlotsError::Full(function(item)),
+        }
+    }
+}
+
+impl<IT> Display for SlotsError<IT> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = match self {
+            SlotsError::WrongOwner => "key used in wrong instance",
+            SlotsError::Vacant => "slot is vacant",
+            SlotsError::OutOfBounds => "index is out of bounds",
+            SlotsError::Full(_) => "collection is full",
+        };
+
+        f.write_str(message)
+    }
+}
+
+impl<IT: Debug> core::error::Error for SlotsError<IT> {}
+
+/// The error returned by the `try_take` method of collections whose keys can't be copied.
+///
+/// Taking an element consumes its key. When the element can't be taken, the key is handed back,
+/// so it can still be used with the collection that returned it.
+///
+/// ```rust
+/// use slots::slots::Slots;
+///
+/// let mut a: Slots<_, 4> = Slots::new();
+/// let mut b: Slots<i32, 4> = Slots::new();
+///
+/// let k = a.store(3).unwrap();
+/// # #[cfg(feature = "runtime_checks")]
+/// match b.try_take(k) {
+///     Ok(_) => unreachable!(),
+///     Err(error) => assert_eq!(3, a.take(error.key)),
+/// }
+/// ```
+#[derive(Debug)]
+pub struct TakeError<K, IT> {
+    /// The reason why the element could not be taken.
+    pub error: SlotsError<IT>,
+    /// The key that was passed in.
+    pub key: K,
+}
+
+impl<K, IT> TakeError<K, IT> {
+    /// Converts the key, keeping the reason of the failure.
+    pub(crate) fn map_key<U>(self, function: impl FnOnce(K) -> U) -> TakeError<U, IT> {
+        TakeError {
+            error: self.error,
+            key: function(self.key),
+        }
+    }
+}
+
+impl<K, IT> Display for TakeError<K, IT> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Display::fmt(&self.error, f)
+    }
+}
+
+impl<K: Debug, IT: Debug> core::error::Error for TakeError<K, IT> {}
@@ -0,0 +1,565 @@
+//! Slots object that detects the use of stale handles.
+//!
+//! Data type that stores values and returns a copyable [`Handle`] that can be used to manipulate
+//! the stored values.
+//!
+//! Like [`UnrestrictedSlots`], anyone holding a handle can access the stored data. Unlike
+//! [`UnrestrictedSlots`], every slot carries a generation counter that is incremented each time
+//! the slot is freed. Handles remember the generation of the slot at the time they were created,
+//! which means that a handle to a removed element will not access a new element that happens
+//! to be stored in the same slot. This avoids the [ABA problem][aba-problem].
+//!
+//! # Store data
+//!
+//! ```rust
+//! use slots::generational::GenerationalSlots;
+//!
+//! let mut slots: GenerationalSlots<_, 2> = GenerationalSlots::new(); // Capacity of 2 elements
+//!
+//! // Store elements
+//! let h1 = slots.store(2).unwrap();
+//! let h2 = slots.store(4).unwrap();
+//!
+//! // Now that the collection is full, the next store will fail and
+//! // return an Err object that holds the original value we wanted to store.
+//! let h3 = slots.store(8);
+//! assert_eq!(h3.err(), Some(8));
+//!
+//! // Storage statistics
+//! assert_eq!(2, slots.capacity()); // this instance can hold at most 2 elements
+//! assert_eq!(2, slots.count()); // there are currently 2 elements stored
+//! ```
+//!
+//! # Stale handles
+//!
+//! Removing an element invalidates every copy of its handle, even if the slot is reused later.
+//!
+//! ```rust
+//! # use slots::generational::GenerationalSlots;
+//! #
+//! # let mut slots: GenerationalSlots<_, 2> = GenerationalSlots::new();
+//! #
+//! let h1 = slots.store(2).unwrap();
+//! let copy = h1;
+//!
+//! assert_eq!(Some(2), slots.take(h1));
+//!
+//! // The slot is reused by the next store
+//! let h2 = slots.store(4).unwrap();
+//! assert_eq!(h1.index(), h2.index());
+//!
+//! // ... but the old handle can't be used to access the new element
+//! assert_eq!(None, slots.read(copy, |&e| e));
+//! assert_eq!(Some(4), slots.read(h2, |&e| e));
+//! ```
+//!
+//! **Note:** generations have the type [`I::Generation`](SlotIndex::Generation) and wrap around,
+//! so a stale handle is accepted again after its slot was reused as many times as the generation
+//! type has values.
+//!
+//! [`UnrestrictedSlots`]: crate::unrestricted::UnrestrictedSlots
+//! [aba-problem]: https://en.wikipedia.org/wiki/ABA_problem
+
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+use core::iter::FusedIterator;
+use core::mem::MaybeUninit;
+use core::ptr::addr_of_mut;
+
+use crate::index::SlotIndex;
+use crate::iterator::*;
+use crate::keyed::{Keyed, RawKey};
+use crate::owner::NoOwnerId;
+use crate::unrestricted::{self, UnrestrictedSlots};
+
+/// The handle used to access stored elements.
+///
+/// A handle identifies a slot, as well as the generation of the element stored in it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Handle<I: SlotIndex = usize> {
+    index: I,
+    generation: I::Generation,
+}
+
+/// Handles don't need owner ids, every copy is accepted by the collection that returned it.
+type HandleKey<I> = RawKey<I, NoOwnerId, <I as SlotIndex>::Generation>;
+
+impl<I: SlotIndex> Handle<I> {
+    pub(crate) fn from_parts(index: I, generation: I::Generation) -> Self {
+        Self { index, generation }
+    }
+
+    fn from_key(key: HandleKey<I>) -> Self {
+        Self::from_parts(I::from_usize(key.index()), key.generation())
+    }
+
+    fn key(self) -> HandleKey<I> {
+        RawKey::new((), self.generation, self.index())
+    }
+
+    /// Returns the index of the slot this handle refers to.
+    pub fn index(&self) -> usize {
+        self.index.to_usize()
+    }
+
+    /// Returns the generation of the element this handle refers to.
+    pub fn generation(&self) -> I::Generation {
+        self.generation
+    }
+}
+
+/// Slots object that detects the use of stale handles.
+///
+/// The struct has three type parameters:
+///  - `IT` is the type of the stored data
+///  - `N` is the number of slots.
+///  - `I` is the type used to store slot indices, see [`SlotIndex`]. Defaults to `usize`.
+///
+/// For more information, see the [module level documentation](crate::generational)
+pub struct GenerationalSlots<IT, const N: usize, I: SlotIndex = usize> {
+    keyed: Keyed<UnrestrictedSlots<IT, N, I>, [I::Generation; N], NoOwnerId>,
+}
+
+impl<IT, const N: usize, I: SlotIndex> Default for GenerationalSlots<IT, N, I> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<IT, const N: usize, I: SlotIndex> GenerationalSlots<IT, N, I> {
+    /// Creates a new, empty GenerationalSlots object.
+    ///
+    /// This function can be used in const context, for example to initialize a `static`.
+    pub const fn new() -> Self {
+        Self {
+            keyed: Keyed::new(UnrestrictedSlots::new(), [I::Generation::ZERO; N]),
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    pub(crate) fn from_parts(
+        generations: [I::Generation; N],
+        inner: UnrestrictedSlots<IT, N, I>,
+    ) -> Self {
+        Self {
+            keyed: Keyed::new(inner, generations),
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    pub(crate) fn as_parts(&self) -> (&[I::Generation; N], &UnrestrictedSlots<IT, N, I>) {
+        (self.keyed.generations(), &self.keyed.inner)
+    }
+
+    /// Initializes an empty GenerationalSlots object in the memory pointed to by `slot`, and
+    /// returns a reference to it.
+    ///
+    /// Unlike [`new`](GenerationalSlots::new), this method does not create the object on the
+    /// stack first, so it can be used to initialize collections that are too large for the stack.
+    ///
+    /// ```
+    /// # use core::mem::MaybeUninit;
+    /// # use slots::generational::GenerationalSlots;
+    /// let mut memory = MaybeUninit::uninit();
+    /// let slots: &mut GenerationalSlots<u32, 1024> = GenerationalSlots::init_in_place(&mut memory);
+    ///
+    /// let h = slots.store(3).unwrap();
+    /// assert_eq!(Some(3), slots.take(h));
+    /// # unsafe { memory.assume_init_drop() };
+    /// ```
+    ///
+    /// **Note:** like with any `MaybeUninit`, the object is not dropped automatically.
+    pub fn init_in_place(slot: &mut MaybeUninit<Self>) -> &mut Self {
+        let this = slot.as_mut_ptr();
+
+        // Safety: the pointer is derived from a mutable reference
+        unsafe {
+            Keyed::init_in_place(addr_of_mut!((*this).keyed), |inner| {
+                UnrestrictedSlots::init_raw(inner)
+            });
+
+            slot.assume_init_mut()
+        }
+    }
+
+    /// Creates a new, empty GenerationalSlots object directly on the heap.
+    ///
+    /// ```
+    /// # use slots::generational::GenerationalSlots;
+    /// let mut slots: Box<GenerationalSlots<u32, 100_000>> = GenerationalSlots::new_boxed();
+    ///
+    /// let h = slots.store(3).unwrap();
+    /// assert_eq!(Some(3), slots.take(h));
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn new_boxed() -> Box<Self> {
+        let mut boxed = Box::new_uninit();
+        Self::init_in_place(&mut boxed);
+
+        // Safety: the object was initialized above
+        unsafe { boxed.assume_init() }
+    }
+
+    /// Returns a read-only iterator.
+    /// The iterator can be used to read data from all occupied slots.
+    ///
+    /// **Note:** Do not rely on the order in which the elements are returned.
+    ///
+    /// ```
+    /// # use slots::generational::GenerationalSlots;
+    /// # let mut slots: GenerationalSlots<_, 4> = GenerationalSlots::new();
+    /// slots.store(2).unwrap();
+    /// slots.store(4).unwrap();
+    /// slots.store(6).unwrap();
+    ///
+    /// assert_eq!(true, slots.iter().any(|&x| x < 3));
+    /// ```
+    pub fn iter(&self) -> Iter<'_, IT> {
+        self.keyed.inner.iter()
+    }
+
+    /// Returns a read-write iterator.
+    /// The iterator can be used to read and modify data from all occupied slots, but it can't remove data.
+    ///
+    /// **Note:** Do not rely on the order in which the elements are returned.
+    ///
+    /// ```
+    /// # use slots::generational::GenerationalSlots;
+    /// # let mut slots: GenerationalSlots<_, 4> = GenerationalSlots::new();
+    /// let h = slots.store(2).unwrap();
+    /// slots.store(4).unwrap();
+    /// slots.store(6).unwrap();
+    ///
+    /// for x in slots.iter_mut() {
+    ///     *x *= 2;
+    /// }
+    ///
+    /// assert_eq!(4, slots.take(h).unwrap());
+    /// ```
+    pub fn iter_mut(&mut self) -> IterMut<'_, IT> {
+        self.keyed.inner.iter_mut()
+    }
+
+    /// Returns a read-only iterator that also returns the index of every element.
+    ///
+    /// **Note:** Do not rely on the order in which the elements are returned.
+    ///
+    /// ```
+    /// # use slots::generational::GenerationalSlots;
+    /// # let mut slots: GenerationalSlots<_, 4> = GenerationalSlots::new();
+    /// let h = slots.store(2).unwrap();
+    ///
+    /// assert_eq!(Some((h.index(), &2)), slots.iter_indexed().next());
+    /// ```
+    pub fn iter_indexed(&self) -> IterIndexed<'_, IT> {
+        self.keyed.inner.iter_indexed()
+    }
+
+    /// Removes every element and returns them in an iterator.
+    ///
+    /// The collection is empty after the iterator is dropped, even if it was not fully consumed.
+    /// Handles returned before the call are stale, like after [`clear`](GenerationalSlots::clear).
+    ///
+    /// **Note:** Do not rely on the order in which the elements are returned.
+    ///
+    /// ```
+    /// # use slots::generational::GenerationalSlots;
+    /// # let mut slots: GenerationalSlots<_, 4> = GenerationalSlots::new();
+    /// let h = slots.store(2).unwrap();
+    /// slots.store(4).unwrap();
+    ///
+    /// assert_eq!(6, slots.drain().sum::<i32>());
+    /// assert_eq!(0, slots.count());
+    /// assert_eq!(false, slots.contains(h));
+    /// ```
+    pub fn drain(&mut self) -> Drain<'_, IT, N, I> {
+        self.keyed.invalidate_keys();
+
+        Drain {
+            inner: self.keyed.inner.drain(),
+        }
+    }
+
+    /// Drops every element, and releases all slots.
+    ///
+    /// Handles returned before the collection was cleared are stale, even if their slots are
+    /// reused.
+    ///
+    /// If the destructor of an element panics, the remaining elements are leaked, and the
+    /// collection is left empty.
+    ///
+    /// ```
+    /// # use slots::generational::GenerationalSlots;
+    /// # let mut slots: GenerationalSlots<_, 4> = GenerationalSlots::new();
+    /// let h = slots.store(2).unwrap();
+    ///
+    /// slots.clear();
+    /// assert_eq!(0, slots.count());
+    ///
+    /// let new = slots.store(3).unwrap();
+    /// assert_eq!(h.index(), new.index());
+    /// assert_eq!(None, slots.get(h));
+    /// ```
+    pub fn clear(&mut self) {
+        // invalidate first, so handles are stale even if a destructor panics
+        self.keyed.invalidate_keys();
+        self.keyed.inner.clear();
+    }
+
+    /// Returns the number of slots
+    ///
+    /// ```
+    /// # use slots::generational::GenerationalSlots;
+    /// let slots: GenerationalSlots<f32, 4> = GenerationalSlots::new();
+    ///
+    /// assert_eq!(4, slots.capacity());
+    /// ```
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns the number of occupied slots
+    ///
+    /// ```
+    /// # use slots::generational::GenerationalSlots;
+    /// let mut slots: GenerationalSlots<_, 4> = GenerationalSlots::new();
+    ///
+    /// assert_eq!(0, slots.count());
+    ///
+    /// slots.store(3).unwrap();
+    /// slots.store(6).unwrap();
+    ///
+    /// assert_eq!(2, slots.count());
+    /// ```
+    pub fn count(&self) -> usize {
+        self.keyed.inner.count()
+    }
+
+    /// Returns whether all the slots are occupied and the next [`store()`](GenerationalSlots::store) will fail.
+    ///
+    /// ```
+    /// # use slots::generational::GenerationalSlots;
+    /// let mut slots: GenerationalSlots<_, 2> = GenerationalSlots::new();
+    ///
+    /// slots.store(3).unwrap();
+    ///
+    /// assert_eq!(false, slots.is_full());
+    ///
+    /// slots.store(6).unwrap();
+    ///
+    /// assert_eq!(true, slots.is_full());
+    /// ```
+    pub fn is_full(&self) -> bool {
+        self.keyed.inner.is_full()
+    }
+
+    /// Returns whether the handle refers to an element that is still stored in the collection.
+    ///
+    /// ```
+    /// # use slots::generational::GenerationalSlots;
+    /// # let mut slots: GenerationalSlots<_, 4> = GenerationalSlots::new();
+    /// let h = slots.store(3).unwrap();
+    ///
+    /// assert_eq!(true, slots.contains(h));
+    ///
+    /// slots.take(h);
+    ///
+    /// assert_eq!(false, slots.contains(h));
+    /// ```
+    pub fn contains(&self, handle: Handle<I>) -> bool {
+        self.keyed.try_get_key(&handle.key()).is_ok()
+    }
+
+    /// Store an element in a free slot and return the handle to access it.
+    ///
+    /// Storing a variable takes ownership over it. If the storage is full,
+    /// the inserted data is returned in the return value.
+    pub fn store(&mut self, item: IT) -> Result<Handle<I>, IT> {
+        self.keyed.store(item).map(Handle::from_key)
+    }
+
+    /// Remove and return the element that belongs to the handle.
+    ///
+    /// This operation is fallible. If `handle` is stale, `None` is returned.
+    pub fn take(&mut self, handle: Handle<I>) -> Option<IT> {
+        self.keyed.try_take(handle.key()).ok()
+    }
+
+    /// Read the element that belongs to the handle.
+    ///
+    /// This operation does not move ownership so the `function` callback must be used
+    /// to access the stored element. The callback may return arbitrary derivative of the element.
+    ///
+    /// This operation is fallible. If `handle` is stale, `None` is returned.
+    ///
+    /// ```
+    /// # use slots::generational::GenerationalSlots;
+    /// # let mut slots: GenerationalSlots<_, 4> = GenerationalSlots::new();
+    ///
+    /// let h = slots.store(3).unwrap();
+    ///
+    /// assert_eq!(Some(4), slots.read(h, |elem| {
+    ///     elem + 1
+    /// }));
+    ///
+    /// slots.take(h);
+    ///
+    /// assert_eq!(None, slots.read(h, |elem| {
+    ///     elem + 1
+    /// }));
+    /// ```
+    pub fn read<T>(&self, handle: Handle<I>, function: impl FnOnce(&IT) -> T) -> Option<T> {
+        self.get(handle).map(function)
+    }
+
+    /// Access the element that belongs to the handle for modification.
+    ///
+    /// This operation does not move ownership so the `function` callback must be used
+    /// to access the stored element. The callback may return arbitrary derivative of the element.
+    ///
+    /// This operation is fallible. If `handle` is stale, `None` is returned.
+    ///
+    /// ```
+    /// # use slots::generational::GenerationalSlots;
+    /// # let mut slots: GenerationalSlots<_, 4> = GenerationalSlots::new();
+    ///
+    /// let h = slots.store(3).unwrap();
+    ///
+    /// assert_eq!(Some("found"), slots.modify(h, |elem| {
+    ///     *elem = *elem + 1;
+    ///
+    ///     "found"
+    /// }));
+    ///
+    /// // Assert that the stored data was modified
+    /// assert_eq!(Some(4), slots.take(h));
+    ///
+    /// assert_eq!(None, slots.modify(h, |elem| {
+    ///     *elem = *elem + 1;
+    ///
+    ///     "found"
+    /// }));
+    /// ```
+    pub fn modify<T>(
+        &mut self,
+        handle: Handle<I>,
+        function: impl FnOnce(&mut IT) -> T,
+    ) -> Option<T> {
+        self.get_mut(handle).map(function)
+    }
+
+    /// Returns a reference to the element that belongs to the handle.
+    ///
+    /// This operation is fallible. If `handle` is stale, `None` is returned.
+    ///
+    /// ```
+    /// # use slots::generational::GenerationalSlots;
+    /// # let mut slots: GenerationalSlots<_, 4> = GenerationalSlots::new();
+    ///
+    /// let h = slots.store(3).unwrap();
+    ///
+    /// assert_eq!(Some(&3), slots.get(h));
+    ///
+    /// slots.take(h);
+    ///
+    /// assert_eq!(None, slots.get(h));
+    /// ```
+    pub fn get(&self, handle: Handle<I>) -> Option<&IT> {
+        self.keyed.try_get_key(&handle.key()).ok()
+    }
+
+    /// Returns a mutable reference to the element that belongs to the handle.
+    ///
+    /// This operation is fallible. If `handle` is stale, `None` is returned.
+    ///
+    /// ```
+    /// # use slots::generational::GenerationalSlots;
+    /// # let mut slots: GenerationalSlots<_, 4> = GenerationalSlots::new();
+    ///
+    /// let h = slots.store(3).unwrap();
+    ///
+    /// if let Some(elem) = slots.get_mut(h) {
+    ///     *elem += 1;
+    /// }
+    ///
+    /// assert_eq!(Some(4), slots.take(h));
+    /// assert_eq!(None, slots.get_mut(h));
+    /// ```
+    pub fn get_mut(&mut self, handle: Handle<I>) -> Option<&mut IT> {
+        self.keyed.try_get_key_mut(&handle.key()).ok()
+    }
+
+    /// Returns mutable references to multiple elements at once.
+    ///
+    /// This operation is fallible. If any of the `handles` is stale, or if the same handle is
+    /// present more than once, `None` is returned.
+    ///
+    /// ```
+    /// # use slots::generational::GenerationalSlots;
+    /// # let mut slots: GenerationalSlots<_, 4> = GenerationalSlots::new();
+    ///
+    /// let h1 = slots.store(3).unwrap();
+    /// let h2 = slots.store(5).unwrap();
+    ///
+    /// if let Some([a, b]) = slots.get_many_mut([h1, h2]) {
+    ///     core::mem::swap(a, b);
+    /// }
+    ///
+    /// assert_eq!(Some(&5), slots.get(h1));
+    /// assert!(slots.get_many_mut([h1, h1]).is_none());
+    /// ```
+    pub fn get_many_mut<const M: usize>(
+        &mut self,
+        handles: [Handle<I>; M],
+    ) -> Option<[&mut IT; M]> {
+        if handles.iter().all(|&handle| self.contains(handle)) {
+            self.keyed
+                .inner
+                .get_many_mut(handles.map(|handle| handle.index()))
+        } else {
+            None
+        }
+    }
+}
+
+impl<'a, IT, const N: usize, I: SlotIndex> IntoIterator for &'a GenerationalSlots<IT, N, I> {
+    type Item = &'a IT;
+    type IntoIter = Iter<'a, IT>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, IT, const N: usize, I: SlotIndex> IntoIterator for &'a mut GenerationalSlots<IT, N, I> {
+    type Item = &'a mut IT;
+    type IntoIter = IterMut<'a, IT>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+/// Iterator that removes every element of a [`GenerationalSlots`] object.
+///
+/// Created by [`GenerationalSlots::drain`]. Elements that are not consumed are dropped together
+/// with the iterator.
+pub struct Drain<'a, IT, const N: usize, I: SlotIndex = usize> {
+    inner: unrestricted::Drain<'a, IT, N, I>,
+}
+
+impl<IT, const N: usize, I: SlotIndex> Iterator for Drain<'_, IT, N, I> {
+    type Item = IT;
+
+    fn next(&mut self) -> Option<IT> {
+        self.inner.next().map(|(_, item)| item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<IT, const N: usize, I: SlotIndex> ExactSizeIterator for Drain<'_, IT, N, I> {}
+
+impl<IT, const N: usize, I: SlotIndex> FusedIterator for Drain<'_, IT, N, I> {}
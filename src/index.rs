@@ -0,0 +1,122 @@
+//! Integer types that can be used to address slots.
+//!
+//! By default, every collection uses `usize` to store slot indices, both in the bookkeeping of
+//! its free slots and in its keys. Collections with a small capacity can use a narrower type to reduce their memory
+//! footprint, by passing it as the last type parameter:
+//!
+//! ```rust
+//! use slots::unrestricted::UnrestrictedSlots;
+//!
+//! let mut slots: UnrestrictedSlots<u8, 200, u8> = UnrestrictedSlots::new();
+//!
+//! let idx = slots.store(5).unwrap();
+//! assert_eq!(Some(5), slots.read(idx, |&e| e));
+//!
+//! assert!(core::mem::size_of_val(&slots) < core::mem::size_of::<UnrestrictedSlots<u8, 200>>());
+//! ```
+//!
+//! The capacity must be representable by the index type. This is verified at compile time:
+//!
+//! ```rust{compile_fail}
+//! use slots::unrestricted::UnrestrictedSlots;
+//!
+//! // 256 slots can't be counted using an u8
+//! let slots: UnrestrictedSlots<u8, 256, u8> = UnrestrictedSlots::new();
+//! ```
+
+use core::fmt::Debug;
+use core::hash::Hash;
+use core::num::{NonZeroU16, NonZeroU32, NonZeroU8, NonZeroUsize};
+
+use crate::private::Sealed;
+
+/// An unsigned integer type that can be used to address slots.
+///
+/// This trait is sealed and is implemented for `u8`, `u16`, `u32` and `usize`.
+pub trait SlotIndex: Sealed + Copy + Eq + Ord + Hash + Debug {
+    /// The non-zero counterpart of the index type, used to store indices in keys.
+    #[doc(hidden)]
+    type NonZero: Copy + Eq + Ord + Hash + Debug;
+
+    /// The type of the per-slot generations of collections that use this index type.
+    ///
+    /// It matches the index type, except for `usize` which uses `u32`.
+    type Generation: SlotIndex;
+
+    /// The largest capacity that can be addressed using this type.
+    const MAX_CAPACITY: usize;
+
+    #[doc(hidden)]
+    const ZERO: Self;
+
+    #[doc(hidden)]
+    fn from_usize(value: usize) -> Self;
+
+    #[doc(hidden)]
+    fn to_usize(self) -> usize;
+
+    /// Stores `index + 1`, so that the zero value is available as a niche.
+    #[doc(hidden)]
+    fn to_non_zero(index: usize) -> Self::NonZero;
+
+    #[doc(hidden)]
+    fn from_non_zero(value: Self::NonZero) -> usize;
+
+    /// Adds one, wrapping around at the largest value of the type. Used for generations.
+    #[doc(hidden)]
+    fn wrapping_next(self) -> Self;
+}
+
+macro_rules! impl_slot_index {
+    ($($ty:ty => $nz:ty, $generation:ty),*) => {
+        $(
+            impl Sealed for $ty {}
+
+            impl SlotIndex for $ty {
+                type NonZero = $nz;
+                type Generation = $generation;
+
+                const MAX_CAPACITY: usize = if (<$ty>::MAX as u128) < (usize::MAX as u128) {
+                    <$ty>::MAX as usize
+                } else {
+                    usize::MAX
+                };
+
+                const ZERO: Self = 0;
+
+                #[inline]
+                fn from_usize(value: usize) -> Self {
+                    debug_assert!(value <= Self::MAX_CAPACITY);
+                    value as $ty
+                }
+
+                #[inline]
+                fn to_usize(self) -> usize {
+                    self as usize
+                }
+
+                #[inline]
+                fn to_non_zero(index: usize) -> Self::NonZero {
+                    <$nz>::new(Self::from_usize(index + 1)).expect("Index out of range")
+                }
+
+                #[inline]
+                fn from_non_zero(value: Self::NonZero) -> usize {
+                    value.get() as usize - 1
+                }
+
+                #[inline]
+                fn wrapping_next(self) -> Self {
+                    self.wrapping_add(1)
+                }
+            }
+        )*
+    };
+}
+
+impl_slot_index!(
+    u8 => NonZeroU8, u8,
+    u16 => NonZeroU16, u16,
+    u32 => NonZeroU32, u32,
+    usize => NonZeroUsize, u32
+);
@@ -1,14 +1,299 @@
-use crate::private::Entry;
+use core::iter::FusedIterator;
+use core::marker::PhantomData;
+use core::mem::{size_of, size_of_val, MaybeUninit};
+use core::slice;
+
+use crate::raw::{load_word, Entry, WORD_BITS};
+
+/// Finds the occupied slots, one word of the occupancy bitmap at a time.
+///
+/// The occupied slots of a word are found using `trailing_zeros` (or `leading_zeros` from the
+/// back), so each word of free slots is skipped using a single load. Scanning stops as soon as
+/// every occupied slot has been found.
+///
+/// The front and the back may scan the same word. Because both ends return the slots in order and
+/// the number of remaining slots is known, they never return the same slot.
+#[derive(Clone)]
+struct Occupied<'a> {
+    bitmap: &'a [u8],
+    /// Index of the word loaded into `front`.
+    front_word: usize,
+    /// Occupancy bits of the front word that have not been returned yet.
+    front: u64,
+    /// Index of the word loaded into `back`.
+    back_word: usize,
+    /// Occupancy bits of the back word that have not been returned yet.
+    back: u64,
+    /// Number of occupied slots that have not been returned yet.
+    remaining: usize,
+}
+
+impl<'a> Occupied<'a> {
+    fn new(bitmap: &'a [u8], count: usize) -> Self {
+        let back_word = bitmap.len().saturating_sub(1) / 8;
+
+        Self {
+            bitmap,
+            front_word: 0,
+            front: Self::load(bitmap, 0),
+            back_word,
+            back: Self::load(bitmap, back_word),
+            remaining: count,
+        }
+    }
+
+    fn load(bitmap: &[u8], word: usize) -> u64 {
+        #[cfg(test)]
+        iter_test::WORDS_LOADED.with(|loaded| loaded.set(loaded.get() + 1));
+
+        load_word(bitmap, word)
+    }
+}
+
+impl Iterator for Occupied<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        while self.front == 0 {
+            self.front_word += 1;
+            if self.front_word * 8 >= self.bitmap.len() {
+                return None;
+            }
+            self.front = Self::load(self.bitmap, self.front_word);
+        }
+
+        let bit = self.front.trailing_zeros() as usize;
+        self.front &= self.front - 1; // clear the lowest set bit
+        self.remaining -= 1;
+
+        Some(self.front_word * WORD_BITS + bit)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl DoubleEndedIterator for Occupied<'_> {
+    fn next_back(&mut self) -> Option<usize> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        while self.back == 0 {
+            if self.back_word == 0 {
+                return None;
+            }
+            self.back_word -= 1;
+            self.back = Self::load(self.bitmap, self.back_word);
+        }
+
+        let bit = (WORD_BITS - 1) - self.back.leading_zeros() as usize;
+        self.back &= !(1 << bit); // clear the highest set bit
+        self.remaining -= 1;
+
+        Some(self.back_word * WORD_BITS + bit)
+    }
+}
+
+/// Read-only iterator to access all occupied slots, together with their indices.
+///
+/// An entry of the collection may be larger than its value, so the values are addressed using
+/// the size of an entry in bytes. This keeps the iterator types independent of the index type.
+pub struct IterIndexed<'a, IT> {
+    occupied: Occupied<'a>,
+    /// The bytes of the entries that were not returned yet, starting at `front`.
+    values: &'a [MaybeUninit<u8>],
+    /// Size of an entry, the first bytes of which hold the value.
+    stride: usize,
+    /// Index of the first entry in `values`.
+    front: usize,
+    _item: PhantomData<&'a IT>,
+}
+
+impl<'a, IT> IterIndexed<'a, IT> {
+    /// # Safety
+    ///
+    /// Every value whose bit is set in `bitmap` must be initialized, and `count` must be
+    /// the number of such values.
+    pub(crate) unsafe fn from_parts<I: Copy>(
+        entries: &'a [Entry<IT, I>],
+        bitmap: &'a [u8],
+        count: usize,
+    ) -> Self {
+        Self {
+            occupied: Occupied::new(bitmap, count),
+            // Safety: the bytes belong to the borrowed entries, and `MaybeUninit` allows any
+            // contents
+            values: slice::from_raw_parts(entries.as_ptr().cast(), size_of_val(entries)),
+            stride: size_of::<Entry<IT, I>>(),
+            front: 0,
+            _item: PhantomData,
+        }
+    }
+
+    /// Returns the value of an entry.
+    ///
+    /// # Safety
+    ///
+    /// The entry must hold an initialized value.
+    unsafe fn value(bytes: &'a [MaybeUninit<u8>]) -> &'a IT {
+        // the entries are aligned for the value, and it is stored at the start of the entry
+        &*bytes.as_ptr().cast::<IT>()
+    }
+}
+
+impl<IT> Clone for IterIndexed<'_, IT> {
+    fn clone(&self) -> Self {
+        Self {
+            occupied: self.occupied.clone(),
+            values: self.values,
+            stride: self.stride,
+            front: self.front,
+            _item: PhantomData,
+        }
+    }
+}
+
+impl<'a, IT> Iterator for IterIndexed<'a, IT> {
+    type Item = (usize, &'a IT);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let idx = self.occupied.next()?;
+        let (entry, rest) = self
+            .values
+            .get((idx - self.front) * self.stride..)?
+            .split_at(self.stride);
+        self.values = rest;
+        self.front = idx + 1;
+
+        // Safety: guaranteed by the caller of `from_parts`
+        Some((idx, unsafe { Self::value(entry) }))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.occupied.size_hint()
+    }
+}
+
+impl<IT> DoubleEndedIterator for IterIndexed<'_, IT> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let idx = self.occupied.next_back()?;
+        let (rest, entry) = self
+            .values
+            .get(..(idx + 1 - self.front) * self.stride)?
+            .split_at((idx - self.front) * self.stride);
+        self.values = rest;
+
+        // Safety: guaranteed by the caller of `from_parts`
+        Some((idx, unsafe { Self::value(entry) }))
+    }
+}
+
+impl<IT> ExactSizeIterator for IterIndexed<'_, IT> {}
+
+impl<IT> FusedIterator for IterIndexed<'_, IT> {}
+
+/// Read-write iterator to access all occupied slots, together with their indices.
+pub struct IterIndexedMut<'a, IT> {
+    occupied: Occupied<'a>,
+    /// The bytes of the entries that were not returned yet, starting at `front`.
+    values: &'a mut [MaybeUninit<u8>],
+    /// Size of an entry, the first bytes of which hold the value.
+    stride: usize,
+    /// Index of the first entry in `values`.
+    front: usize,
+    _item: PhantomData<&'a mut IT>,
+}
+
+impl<'a, IT> IterIndexedMut<'a, IT> {
+    /// # Safety
+    ///
+    /// Every value whose bit is set in `bitmap` must be initialized, and `count` must be
+    /// the number of such values.
+    pub(crate) unsafe fn from_parts<I: Copy>(
+        entries: &'a mut [Entry<IT, I>],
+        bitmap: &'a [u8],
+        count: usize,
+    ) -> Self {
+        Self {
+            occupied: Occupied::new(bitmap, count),
+            // Safety: the bytes belong to the borrowed entries, and `MaybeUninit` allows any
+            // contents
+            values: slice::from_raw_parts_mut(entries.as_mut_ptr().cast(), size_of_val(entries)),
+            stride: size_of::<Entry<IT, I>>(),
+            front: 0,
+            _item: PhantomData,
+        }
+    }
+
+    /// Returns the value of an entry.
+    ///
+    /// # Safety
+    ///
+    /// The entry must hold an initialized value.
+    unsafe fn value(bytes: &'a mut [MaybeUninit<u8>]) -> &'a mut IT {
+        // the entries are aligned for the value, and it is stored at the start of the entry
+        &mut *bytes.as_mut_ptr().cast::<IT>()
+    }
+}
+
+impl<'a, IT> Iterator for IterIndexedMut<'a, IT> {
+    type Item = (usize, &'a mut IT);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let idx = self.occupied.next()?;
+        let (entry, rest) = core::mem::take(&mut self.values)
+            .get_mut((idx - self.front) * self.stride..)?
+            .split_at_mut(self.stride);
+        self.values = rest;
+        self.front = idx + 1;
+
+        // Safety: guaranteed by the caller of `from_parts`
+        Some((idx, unsafe { Self::value(entry) }))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.occupied.size_hint()
+    }
+}
+
+impl<IT> DoubleEndedIterator for IterIndexedMut<'_, IT> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let idx = self.occupied.next_back()?;
+        let (rest, entry) = core::mem::take(&mut self.values)
+            .get_mut(..(idx + 1 - self.front) * self.stride)?
+            .split_at_mut((idx - self.front) * self.stride);
+        self.values = rest;
+
+        // Safety: guaranteed by the caller of `from_parts`
+        Some((idx, unsafe { Self::value(entry) }))
+    }
+}
+
+impl<IT> ExactSizeIterator for IterIndexedMut<'_, IT> {}
+
+impl<IT> FusedIterator for IterIndexedMut<'_, IT> {}
 
 /// Read-only iterator to access all occupied slots.
 pub struct Iter<'a, IT> {
-    inner: core::slice::Iter<'a, Entry<IT>>,
+    inner: IterIndexed<'a, IT>,
 }
 
 impl<'a, IT> Iter<'a, IT> {
-    pub(crate) fn from_entry_slice(inner: &'a [Entry<IT>]) -> Self {
+    pub(crate) fn from_indexed(inner: IterIndexed<'a, IT>) -> Self {
+        Self { inner }
+    }
+}
+
+impl<IT> Clone for Iter<'_, IT> {
+    fn clone(&self) -> Self {
         Self {
-            inner: inner.iter(),
+            inner: self.inner.clone(),
         }
     }
 }
@@ -17,25 +302,32 @@ impl<'a, IT> Iterator for Iter<'a, IT> {
     type Item = &'a IT;
 
     fn next(&mut self) -> Option<Self::Item> {
-        for slot in self.inner.by_ref() {
-            if let Entry::Used(ref item) = slot {
-                return Some(item);
-            }
-        }
-        None
+        self.inner.next().map(|(_, item)| item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
     }
 }
 
+impl<IT> DoubleEndedIterator for Iter<'_, IT> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(_, item)| item)
+    }
+}
+
+impl<IT> ExactSizeIterator for Iter<'_, IT> {}
+
+impl<IT> FusedIterator for Iter<'_, IT> {}
+
 /// Read-write iterator to access all occupied slots.
 pub struct IterMut<'a, IT> {
-    inner: core::slice::IterMut<'a, Entry<IT>>,
+    inner: IterIndexedMut<'a, IT>,
 }
 
 impl<'a, IT> IterMut<'a, IT> {
-    pub(crate) fn from_entry_slice(inner: &'a mut [Entry<IT>]) -> Self {
-        Self {
-            inner: inner.iter_mut(),
-        }
+    pub(crate) fn from_indexed(inner: IterIndexedMut<'a, IT>) -> Self {
+        Self { inner }
     }
 }
 
@@ -43,19 +335,35 @@ impl<'a, IT> Iterator for IterMut<'a, IT> {
     type Item = &'a mut IT;
 
     fn next(&mut self) -> Option<Self::Item> {
-        for slot in self.inner.by_ref() {
-            if let Entry::Used(ref mut item) = slot {
-                return Some(item);
-            }
-        }
-        None
+        self.inner.next().map(|(_, item)| item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<IT> DoubleEndedIterator for IterMut<'_, IT> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|(_, item)| item)
     }
 }
 
+impl<IT> ExactSizeIterator for IterMut<'_, IT> {}
+
+impl<IT> FusedIterator for IterMut<'_, IT> {}
+
 #[cfg(test)]
 mod iter_test {
+    use core::cell::Cell;
+
     use crate::{slots::Slots, unrestricted::UnrestrictedSlots};
 
+    std::thread_local! {
+        /// Number of occupancy bitmap words loaded by the iterators of the current thread.
+        pub(super) static WORDS_LOADED: Cell<usize> = const { Cell::new(0) };
+    }
+
     #[test]
     fn sanity_check() {
         let mut slots: Slots<_, 3> = Slots::new();
@@ -89,4 +397,159 @@ mod iter_test {
 
         assert_eq!(Some(4), slots.take(k2));
     }
+
+    #[test]
+    fn sparse_collection_across_word_boundaries() {
+        let mut slots: UnrestrictedSlots<usize, 4096> = UnrestrictedSlots::new();
+
+        let keys: Vec<usize> = (0..4096).map(|i| slots.store(i).unwrap()).collect();
+
+        // keep a few elements around the edges of the occupancy words
+        let kept = [0, 1, 62, 63, 64, 65, 127, 128, 2000, 4031, 4032, 4095];
+        for key in keys {
+            if !kept.contains(&slots.get(key).copied().unwrap()) {
+                slots.take(key);
+            }
+        }
+
+        let mut found: Vec<usize> = slots.iter().copied().collect();
+        found.sort_unstable();
+        assert_eq!(kept.as_slice(), found.as_slice());
+
+        for item in slots.iter_mut() {
+            *item += 1;
+        }
+        assert_eq!(
+            kept.iter().sum::<usize>() + kept.len(),
+            slots.iter().sum::<usize>()
+        );
+    }
+
+    #[test]
+    fn capacity_not_multiple_of_word_size() {
+        let mut slots: UnrestrictedSlots<_, 70, u8> = UnrestrictedSlots::new();
+        while slots.store(1).is_ok() {}
+
+        assert_eq!(70, slots.iter().count());
+        assert_eq!(70, slots.iter_mut().count());
+
+        slots.take(0);
+        slots.take(69);
+        assert_eq!(68, slots.iter().count());
+
+        let empty: UnrestrictedSlots<u32, 0> = UnrestrictedSlots::new();
+        assert_eq!(None, empty.iter().next());
+    }
+
+    #[test]
+    fn indices_address_the_returned_elements() {
+        let mut slots: UnrestrictedSlots<_, 100, u8> = UnrestrictedSlots::new();
+
+        let keys: Vec<usize> = (0..100).map(|i| slots.store(i * 2).unwrap()).collect();
+        for &key in keys.iter().skip(1).step_by(3) {
+            slots.take(key);
+        }
+
+        for (index, item) in slots.iter_indexed_mut() {
+            *item += index;
+        }
+
+        let mut count = 0;
+        for (index, &item) in slots.iter_indexed() {
+            assert_eq!(Some(&item), slots.get(index));
+            count += 1;
+        }
+        assert_eq!(slots.count(), count);
+    }
+
+    #[test]
+    fn size_hint_is_exact() {
+        let mut slots: UnrestrictedSlots<_, 200> = UnrestrictedSlots::new();
+        let keys: Vec<usize> = (0..150).map(|i| slots.store(i).unwrap()).collect();
+        for &key in keys.iter().step_by(4) {
+            slots.take(key);
+        }
+
+        let mut iter = slots.iter();
+        let mut expected = slots.count();
+        assert_eq!(expected, iter.len());
+        while iter.next().is_some() {
+            expected -= 1;
+            assert_eq!((expected, Some(expected)), iter.size_hint());
+
+            if iter.next_back().is_some() {
+                expected -= 1;
+                assert_eq!(expected, iter.len());
+            }
+        }
+        assert_eq!(0, expected);
+        assert_eq!(None, iter.next_back());
+    }
+
+    #[test]
+    fn both_ends_meet_without_overlap() {
+        let mut slots: UnrestrictedSlots<_, 130> = UnrestrictedSlots::new();
+        let keys: Vec<usize> = (0..130).map(|i| slots.store(i).unwrap()).collect();
+        for &key in keys.iter().skip(2).step_by(5) {
+            slots.take(key);
+        }
+
+        let forward: Vec<usize> = slots.iter_indexed().map(|(index, _)| index).collect();
+        let mut backward: Vec<usize> = slots.iter_indexed().rev().map(|(index, _)| index).collect();
+        backward.reverse();
+        assert_eq!(forward, backward);
+
+        // alternate between the ends, every element is visited exactly once
+        let mut iter = slots.iter_indexed_mut();
+        let mut visited = Vec::new();
+        loop {
+            match (iter.next(), iter.next_back()) {
+                (None, None) => break,
+                (front, back) => visited.extend(front.into_iter().chain(back).map(|(i, _)| i)),
+            }
+        }
+        visited.sort_unstable();
+        assert_eq!(forward, visited);
+    }
+
+    #[test]
+    fn iterators_can_be_cloned_and_used_in_for_loops() {
+        let mut slots: UnrestrictedSlots<_, 4> = UnrestrictedSlots::new();
+        slots.store(1).unwrap();
+        slots.store(2).unwrap();
+
+        let mut iter = slots.iter();
+        iter.next();
+        assert_eq!(iter.clone().count(), iter.count());
+
+        for item in &mut slots {
+            *item *= 10;
+        }
+
+        let mut sum = 0;
+        for item in &slots {
+            sum += item;
+        }
+        assert_eq!(30, sum);
+        assert_eq!(vec![&10, &20], slots.iter().rev().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn free_slots_are_skipped_a_word_at_a_time() {
+        let mut slots: UnrestrictedSlots<u16, 4096> = UnrestrictedSlots::new();
+        let keys: Vec<usize> = (0..=2000).map(|i| slots.store(i).unwrap()).collect();
+        for &key in &keys[..2000] {
+            slots.take(key);
+        }
+        let word = keys[2000] / 64;
+
+        WORDS_LOADED.set(0);
+        assert_eq!(vec![&2000], slots.iter().collect::<Vec<_>>());
+        // both ends are loaded up front, then the front scans up to the word of the element
+        assert_eq!(2 + word, WORDS_LOADED.get());
+
+        WORDS_LOADED.set(0);
+        assert_eq!(vec![&2000], slots.iter().rev().collect::<Vec<_>>());
+        assert_eq!(2 + (4096 / 64 - 1 - word), WORDS_LOADED.get());
+    }
 }
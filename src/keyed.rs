@@ -0,0 +1,311 @@
+//! Key based access control shared by [`Slots`], [`BoxSlots`] and [`BorrowedSlots`].
+//!
+//! [`Keyed`] wraps a collection that gives access to a [`RawSlots`] object, and implements every
+//! operation that verifies a key. The public collections only differ in their storage, so they
+//! are thin wrappers around a `Keyed` object, and their key types are thin wrappers around
+//! [`RawKey`]. The key types are kept separate, so a key can't even be passed to a collection of
+//! a different kind. The only exception is [`ConcurrentSlots`], which shares the key type of
+//! `Slots` and verifies the owner id itself.
+//!
+//! Collections that can release every slot without consuming the keys, using `clear` or
+//! `drain`, keep a generation for every slot. Keys record the generation of their slot, and
+//! releasing the slots this way increments the generations, so the old keys are rejected.
+//!
+//! [`Slots`]: crate::slots::Slots
+//! [`BoxSlots`]: crate::boxed::BoxSlots
+//! [`BorrowedSlots`]: crate::borrowed::BorrowedSlots
+//! [`ConcurrentSlots`]: crate::concurrent::ConcurrentSlots
+
+use core::fmt::Debug;
+use core::ptr::addr_of_mut;
+
+use crate::error::{SlotsError, TakeError};
+use crate::index::SlotIndex;
+use crate::owner::OwnerIdSource;
+use crate::raw::{RawSlots, Storage};
+
+/// A collection that is built on a [`RawSlots`] object.
+pub(crate) trait RawAccess {
+    type Storage: Storage;
+
+    fn raw(&self) -> &RawSlots<Self::Storage>;
+
+    fn raw_mut(&mut self) -> &mut RawSlots<Self::Storage>;
+}
+
+impl<S: Storage> RawAccess for RawSlots<S> {
+    type Storage = S;
+
+    fn raw(&self) -> &RawSlots<S> {
+        self
+    }
+
+    fn raw_mut(&mut self) -> &mut RawSlots<S> {
+        self
+    }
+}
+
+/// The generation of every slot of a collection.
+pub(crate) trait Generations {
+    type Generation: Copy + Eq + Debug;
+
+    /// Initializes every generation in place.
+    ///
+    /// # Safety
+    ///
+    /// `this` must be valid for writes and properly aligned.
+    unsafe fn init_in_place(this: *mut Self);
+
+    /// Returns the generation of a slot, or `None` if the slot is outside the collection.
+    fn get(&self, idx: usize) -> Option<Self::Generation>;
+
+    /// Moves a slot to the next generation. Generations wrap around.
+    fn bump(&mut self, idx: usize);
+}
+
+impl<I: SlotIndex, const N: usize> Generations for [I; N] {
+    type Generation = I;
+
+    unsafe fn init_in_place(this: *mut Self) {
+        let generations = this.cast::<I>();
+        for idx in 0..N {
+            generations.add(idx).write(I::ZERO);
+        }
+    }
+
+    fn get(&self, idx: usize) -> Option<I> {
+        <[I]>::get(self, idx).copied()
+    }
+
+    fn bump(&mut self, idx: usize) {
+        self[idx] = self[idx].wrapping_next();
+    }
+}
+
+/// Collections that can only release a slot by consuming its key don't need generations.
+impl Generations for () {
+    type Generation = ();
+
+    unsafe fn init_in_place(_: *mut Self) {}
+
+    fn get(&self, _: usize) -> Option<()> {
+        Some(())
+    }
+
+    fn bump(&mut self, _: usize) {}
+}
+
+type Item<C> = <<C as RawAccess>::Storage as Storage>::Item;
+type Index<C> = <<C as RawAccess>::Storage as Storage>::Index;
+type Key<C, G, O> = RawKey<Index<C>, O, <G as Generations>::Generation>;
+type TakeResult<C, G, O> = Result<Item<C>, TakeError<Key<C, G, O>, Item<C>>>;
+
+/// The contents of a key: the owner id of the collection that returned it, the generation and
+/// the index of the slot.
+///
+/// The index is stored as a non-zero value, so `Option<RawKey>` is the same size as `RawKey`.
+#[derive(Debug)]
+pub(crate) struct RawKey<I: SlotIndex, O: OwnerIdSource, G> {
+    owner_id: O::Id,
+    generation: G,
+    index: I::NonZero,
+}
+
+impl<I: SlotIndex, O: OwnerIdSource, G> RawKey<I, O, G> {
+    /// Creates the key of a slot from its parts.
+    pub(crate) fn new(owner_id: O::Id, generation: G, idx: usize) -> Self {
+        Self {
+            owner_id,
+            generation,
+            index: I::to_non_zero(idx),
+        }
+    }
+
+    #[cfg(target_has_atomic = "ptr")]
+    pub(crate) fn owner_id(&self) -> O::Id {
+        self.owner_id
+    }
+
+    pub(crate) fn index(&self) -> usize {
+        I::from_non_zero(self.index)
+    }
+}
+
+impl<I: SlotIndex, O: OwnerIdSource, G: Copy> RawKey<I, O, G> {
+    pub(crate) fn generation(&self) -> G {
+        self.generation
+    }
+}
+
+/// Access control that only allows modifying an element using the key that was returned when
+/// it was stored.
+pub(crate) struct Keyed<C, G, O: OwnerIdSource> {
+    /// The owner id is assigned when the first element is stored.
+    id: O::Id,
+    generations: G,
+    pub(crate) inner: C,
+}
+
+impl<C, G: Generations, O: OwnerIdSource> Keyed<C, G, O> {
+    pub(crate) const fn new(inner: C, generations: G) -> Self {
+        Self {
+            id: O::UNASSIGNED,
+            generations,
+            inner,
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    pub(crate) fn generations(&self) -> &G {
+        &self.generations
+    }
+
+    /// Returns the current generation of a slot, or `None` if the slot is outside the collection.
+    pub(crate) fn generation(&self, idx: usize) -> Option<G::Generation> {
+        self.generations.get(idx)
+    }
+
+    /// Initializes the access control in place, and the wrapped collection using `init_inner`.
+    ///
+    /// # Safety
+    ///
+    /// `this` must be valid for writes and properly aligned, and `init_inner` must initialize the
+    /// object it receives. Any previous value is overwritten without being dropped.
+    pub(crate) unsafe fn init_in_place(this: *mut Self, init_inner: impl FnOnce(*mut C)) {
+        addr_of_mut!((*this).id).write(O::UNASSIGNED);
+        G::init_in_place(addr_of_mut!((*this).generations));
+        init_inner(addr_of_mut!((*this).inner));
+    }
+}
+
+impl<C: RawAccess, G: Generations, O: OwnerIdSource> Keyed<C, G, O> {
+    fn assign_id(&mut self) -> O::Id {
+        if self.id == O::UNASSIGNED {
+            self.id = O::next_id();
+        }
+        self.id
+    }
+
+    /// Makes every key that was returned so far unusable with this instance. Must be called
+    /// before the slots are released without consuming their keys.
+    pub(crate) fn invalidate_keys(&mut self) {
+        // a new owner id is assigned by the next store
+        self.id = O::UNASSIGNED;
+
+        for (idx, _) in self.inner.raw().iter_indexed() {
+            self.generations.bump(idx);
+        }
+    }
+
+    fn is_valid(&self, key: &Key<C, G, O>) -> bool {
+        key.owner_id == self.id && self.generations.get(key.index()) == Some(key.generation)
+    }
+
+    fn verify_key(&self, key: &Key<C, G, O>) {
+        assert!(self.is_valid(key), "Key used in wrong instance");
+    }
+
+    fn check_key(&self, key: &Key<C, G, O>) -> Result<(), SlotsError<Item<C>>> {
+        if self.is_valid(key) {
+            Ok(())
+        } else {
+            Err(SlotsError::WrongOwner)
+        }
+    }
+
+    /// Returns the key of a slot that is occupied, or about to be.
+    fn key(&mut self, idx: usize) -> Key<C, G, O> {
+        RawKey {
+            owner_id: self.assign_id(),
+            generation: self.generations.get(idx).expect("Index out of range"),
+            index: Index::<C>::to_non_zero(idx),
+        }
+    }
+
+    /// Returns the key that the next stored element will receive, or `None` if the collection is
+    /// full.
+    pub(crate) fn next_key(&mut self) -> Option<Key<C, G, O>> {
+        let idx = self.inner.raw().peek_free()?;
+
+        Some(self.key(idx))
+    }
+
+    pub(crate) fn store(&mut self, item: Item<C>) -> Result<Key<C, G, O>, Item<C>> {
+        let idx = self.inner.raw_mut().store(item)?;
+
+        Ok(self.key(idx))
+    }
+
+    pub(crate) fn take(&mut self, key: Key<C, G, O>) -> Item<C> {
+        self.verify_key(&key);
+
+        let item = self
+            .inner
+            .raw_mut()
+            .take(key.index())
+            .ok()
+            .expect("Invalid key");
+        self.generations.bump(key.index());
+
+        item
+    }
+
+    pub(crate) fn try_take(&mut self, key: Key<C, G, O>) -> TakeResult<C, G, O> {
+        if let Err(error) = self.check_key(&key) {
+            return Err(TakeError { error, key });
+        }
+
+        match self.inner.raw_mut().take(key.index()) {
+            Ok(item) => {
+                self.generations.bump(key.index());
+                Ok(item)
+            }
+            Err(error) => Err(TakeError { error, key }),
+        }
+    }
+
+    pub(crate) fn get(&self, key: &Key<C, G, O>) -> &Item<C> {
+        self.verify_key(key);
+
+        self.inner.raw().get(key.index()).ok().expect("Invalid key")
+    }
+
+    pub(crate) fn get_mut(&mut self, key: &Key<C, G, O>) -> &mut Item<C> {
+        self.verify_key(key);
+
+        self.inner
+            .raw_mut()
+            .get_mut(key.index())
+            .ok()
+            .expect("Invalid key")
+    }
+
+    pub(crate) fn try_get_key(&self, key: &Key<C, G, O>) -> Result<&Item<C>, SlotsError<Item<C>>> {
+        self.check_key(key)?;
+
+        self.inner.raw().get(key.index())
+    }
+
+    pub(crate) fn try_get_key_mut(
+        &mut self,
+        key: &Key<C, G, O>,
+    ) -> Result<&mut Item<C>, SlotsError<Item<C>>> {
+        self.check_key(key)?;
+
+        self.inner.raw_mut().get_mut(key.index())
+    }
+
+    pub(crate) fn get_many_mut<const M: usize>(
+        &mut self,
+        keys: [&Key<C, G, O>; M],
+    ) -> [&mut Item<C>; M] {
+        for key in keys {
+            self.verify_key(key);
+        }
+
+        self.inner
+            .raw_mut()
+            .get_many_mut(keys.map(RawKey::index))
+            .expect("Invalid key")
+    }
+}
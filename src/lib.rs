@@ -5,13 +5,80 @@
 //! - Read, modify: use the given handle to access the data without removal
 //! - Take: use the given handle to remove data
 //!
-//! There are two variations of this data structure:
+//! There are three variations of this data structure:
 //!  * [`Slots`][crate::slots], where elements can only be modified using a `Key` that can't be copied
 //!  * [`UnrestrictedSlots`][crate::unrestricted], where elements are free to be modified by anyone
+//!  * [`GenerationalSlots`][crate::generational], where elements are free to be modified by anyone,
+//!    but handles to removed elements are detected and rejected
+//!
+//! [`BrandedSlots`][crate::branded] provides the access control of `Slots`, but verifies at compile
+//! time that keys are only used with the collection that returned them.
+//!
+//! The collections in [`borrowed`][crate::borrowed] manage a buffer that is provided by the caller,
+//! instead of owning their storage.
+//!
+//! With the `alloc` feature enabled, [`BoxSlots`][crate::boxed] provides the access control of
+//! `Slots` with a capacity that is chosen at runtime.
+//!
+//! [`ConcurrentSlots`][crate::concurrent] provides the access control of `Slots`, and can be shared
+//! between threads without a lock. With the `critical-section` feature enabled,
+//! [`SharedSlots`][crate::shared] wraps a `Slots` object in a critical section based lock, so it
+//! can be shared with interrupt handlers on targets without atomic instructions.
+//!
+//! [`DenseSlots`][crate::dense] keeps the stored elements in a contiguous array, so they can be
+//! iterated as a slice.
+//!
+//! The collections in [`secondary`][crate::secondary] attach extra data to the elements of
+//! another collection.
+//!
+//! # Serialization
+//!
+//! With the `serde` feature enabled, [`UnrestrictedSlots`][crate::unrestricted],
+//! [`GenerationalSlots`][crate::generational] and [`Slots`][crate::slots] implement `Serialize`
+//! and `Deserialize`. A collection is serialized as a struct:
+//!
+//!  * `items` holds the `(index, element)` pairs of the occupied slots. Deserializing places every
+//!    element back into the slot it was taken from, so indices and handles remain valid across
+//!    a round-trip.
+//!  * `free` lists the released slots in the order in which they are reused, so a restored
+//!    collection hands out its free slots in the same order as the original one. Free slots that
+//!    are not listed are handed out after the listed ones, highest index first.
+//!
+//! `GenerationalSlots` and `Slots` wrap this struct in an `items` field, and store the generation
+//! of every slot in a `generations` field, so stale handles are still detected after a round-trip.
+//!
+//! `Slots` keys can't be serialized, because they are unique and can't be copied. Deserializing a
+//! `Slots` object moves its occupied slots to the next generation, so keys from before the
+//! round-trip are rejected, regardless of the owner id strategy. The restored elements can be
+//! accessed using their indices.
 
 #![cfg_attr(not(test), no_std)]
 
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+pub mod borrowed;
+#[cfg(feature = "alloc")]
+pub mod boxed;
+
+pub mod branded;
+#[cfg(target_has_atomic = "ptr")]
+pub mod concurrent;
+pub mod dense;
+pub mod error;
+pub mod generational;
+pub mod index;
 pub mod iterator;
+mod keyed;
+pub mod owner;
 mod private;
+mod raw;
+pub mod secondary;
+#[cfg(feature = "serde")]
+mod serde_impl;
+#[cfg(feature = "critical-section")]
+pub mod shared;
 pub mod slots;
+#[cfg(target_has_atomic = "ptr")]
+mod sync;
 pub mod unrestricted;
@@ -0,0 +1,187 @@
+//! Strategies to allocate the owner ids used to verify keys.
+//!
+//! Every [`Slots`] instance receives a unique owner id, which is stored in the keys it returns.
+//! Using a key with a different instance is detected by comparing the ids. The way ids are
+//! allocated is selected by the last type parameter of [`Slots`], which must implement
+//! [`OwnerIdSource`]:
+//!
+//!  * [`AtomicOwnerId`] uses an atomic counter. Requires atomic compare-and-swap instructions.
+//!  * [`PortableAtomicOwnerId`] uses an atomic counter provided by the
+//!    [`portable-atomic`](https://docs.rs/portable-atomic) crate. Requires the `portable-atomic` feature.
+//!  * [`CriticalSectionOwnerId`] uses a counter protected by a critical section provided by the
+//!    [`critical-section`](https://docs.rs/critical-section) crate. Requires the `critical-section` feature.
+//!  * [`NoOwnerId`] does not allocate ids, keys are not verified and take up less memory.
+//!
+//! When the type parameter is not specified, [`DefaultOwnerId`] is used. With the `runtime_checks`
+//! feature enabled, this is the first available strategy from the following list: [`AtomicOwnerId`],
+//! [`PortableAtomicOwnerId`], [`CriticalSectionOwnerId`]. With the `runtime_checks` feature disabled,
+//! the default is [`NoOwnerId`].
+//!
+//! ```rust
+//! use slots::owner::NoOwnerId;
+//! use slots::slots::Slots;
+//!
+//! // Keys of this instance are never verified
+//! let mut slots: Slots<u32, 4, usize, NoOwnerId> = Slots::new();
+//!
+//! let key = slots.store(5).unwrap();
+//! assert_eq!(5, slots.take(key));
+//! ```
+//!
+//! [`Slots`]: crate::slots::Slots
+
+use core::fmt::Debug;
+
+/// Allocates owner ids.
+pub trait OwnerIdSource {
+    /// The type of the owner id.
+    type Id: Copy + Eq + Debug;
+
+    /// The owner id of instances that have not returned any keys yet.
+    ///
+    /// This value must never be returned by [`next_id`](OwnerIdSource::next_id), unless every
+    /// id is the same.
+    const UNASSIGNED: Self::Id;
+
+    /// Returns a new, unique owner id.
+    ///
+    /// Ids are never reused. Implementations that run out of ids panic instead of wrapping
+    /// around, like `std::thread::ThreadId` does.
+    fn next_id() -> Self::Id;
+}
+
+/// Called when a counter has handed out every id. Wrapping around would reuse the ids of
+/// instances that may still be alive.
+#[cfg(any(
+    target_has_atomic = "ptr",
+    feature = "portable-atomic",
+    feature = "critical-section"
+))]
+#[cold]
+fn exhausted() -> ! {
+    panic!("Owner ids are exhausted")
+}
+
+/// Allocates owner ids using a `core::sync::atomic::AtomicUsize` counter.
+#[cfg(target_has_atomic = "ptr")]
+#[derive(Debug)]
+pub struct AtomicOwnerId;
+
+#[cfg(target_has_atomic = "ptr")]
+impl OwnerIdSource for AtomicOwnerId {
+    type Id = usize;
+
+    const UNASSIGNED: usize = 0;
+
+    fn next_id() -> usize {
+        use core::sync::atomic::{AtomicUsize, Ordering};
+
+        static COUNTER: AtomicUsize = AtomicUsize::new(AtomicOwnerId::UNASSIGNED + 1);
+
+        COUNTER
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |id| id.checked_add(1))
+            .unwrap_or_else(|_| exhausted())
+    }
+}
+
+/// Allocates owner ids using a `portable_atomic::AtomicUsize` counter.
+#[cfg(feature = "portable-atomic")]
+#[derive(Debug)]
+pub struct PortableAtomicOwnerId;
+
+#[cfg(feature = "portable-atomic")]
+impl OwnerIdSource for PortableAtomicOwnerId {
+    type Id = usize;
+
+    const UNASSIGNED: usize = 0;
+
+    fn next_id() -> usize {
+        use portable_atomic::{AtomicUsize, Ordering};
+
+        static COUNTER: AtomicUsize = AtomicUsize::new(PortableAtomicOwnerId::UNASSIGNED + 1);
+
+        COUNTER
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |id| id.checked_add(1))
+            .unwrap_or_else(|_| exhausted())
+    }
+}
+
+/// Allocates owner ids using a counter protected by a critical section.
+#[cfg(feature = "critical-section")]
+#[derive(Debug)]
+pub struct CriticalSectionOwnerId;
+
+#[cfg(feature = "critical-section")]
+impl OwnerIdSource for CriticalSectionOwnerId {
+    type Id = usize;
+
+    const UNASSIGNED: usize = 0;
+
+    fn next_id() -> usize {
+        use core::cell::Cell;
+        use critical_section::Mutex;
+
+        static COUNTER: Mutex<Cell<usize>> =
+            Mutex::new(Cell::new(CriticalSectionOwnerId::UNASSIGNED + 1));
+
+        let id = critical_section::with(|cs| {
+            let counter = COUNTER.borrow(cs);
+            let id = counter.get();
+            counter.set(id.checked_add(1)?);
+            Some(id)
+        });
+
+        // panic outside of the critical section
+        id.unwrap_or_else(|| exhausted())
+    }
+}
+
+/// Does not allocate owner ids. Keys are not verified.
+///
+/// **Important:** extra care must be taken to only use keys with the collection that returned them.
+#[derive(Debug)]
+pub struct NoOwnerId;
+
+impl OwnerIdSource for NoOwnerId {
+    type Id = ();
+
+    const UNASSIGNED: () = ();
+
+    fn next_id() {}
+}
+
+/// The owner id strategy used when none is specified.
+#[cfg(not(feature = "runtime_checks"))]
+pub type DefaultOwnerId = NoOwnerId;
+
+/// The owner id strategy used when none is specified.
+#[cfg(all(feature = "runtime_checks", target_has_atomic = "ptr"))]
+pub type DefaultOwnerId = AtomicOwnerId;
+
+/// The owner id strategy used when none is specified.
+#[cfg(all(
+    feature = "runtime_checks",
+    not(target_has_atomic = "ptr"),
+    feature = "portable-atomic"
+))]
+pub type DefaultOwnerId = PortableAtomicOwnerId;
+
+/// The owner id strategy used when none is specified.
+#[cfg(all(
+    feature = "runtime_checks",
+    not(target_has_atomic = "ptr"),
+    not(feature = "portable-atomic"),
+    feature = "critical-section"
+))]
+pub type DefaultOwnerId = CriticalSectionOwnerId;
+
+#[cfg(all(
+    feature = "runtime_checks",
+    not(target_has_atomic = "ptr"),
+    not(feature = "portable-atomic"),
+    not(feature = "critical-section")
+))]
+compile_error!(
+    "The `runtime_checks` feature requires atomic instructions on this target. \
+     Enable the `portable-atomic` or `critical-section` feature, or disable `runtime_checks`."
+);
@@ -1,15 +1,6 @@
 //! **Ignore me!** This file contains implementation details
 //! that are conceptually private but must be technically public.
 
+/// Prevents implementing [`SlotIndex`](crate::index::SlotIndex) outside of this crate.
 #[doc(hidden)]
-pub enum Entry<IT> {
-    Used(IT),
-    EmptyNext(usize),
-    EmptyLast,
-}
-
-impl<IT> Default for Entry<IT> {
-    fn default() -> Self {
-        Entry::EmptyLast
-    }
-}
+pub trait Sealed {}
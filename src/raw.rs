@@ -0,0 +1,605 @@
+//! The slot allocator shared by every collection in this crate, except the concurrent ones.
+//!
+//! [`RawSlots`] implements the free slot stack over any [`Storage`], which may be an inline array,
+//! a heap allocation or a borrowed buffer. The public collections wrap a `RawSlots` object and
+//! add their own access control on top of it.
+//!
+//! Slots are stored as two separate arrays:
+//!
+//!  * the [`Entry`] array. An entry holds the value while the slot is occupied. A released slot
+//!    holds the link to the next slot of the stack of released slots instead, so the links need
+//!    no memory of their own, and an entry is only as large as the larger of the value and the
+//!    index type.
+//!  * a packed occupancy bitmap, one bit per slot, which is read in 64 bit words. Iterators use
+//!    it to skip 64 free slots at a time.
+//!
+//! Slots that have never been used are handed out from the highest index downwards, released
+//! slots are reused in LIFO order. Both take constant time.
+//!
+//! The bitmap only needs one bit per slot, but stable Rust can't size an array using an expression
+//! of `N`, so collections with a capacity known at compile time reserve one byte per slot for it.
+//! Heap allocated collections use the exact length.
+
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+use core::mem::{needs_drop, ManuallyDrop, MaybeUninit};
+use core::ptr::addr_of_mut;
+
+use crate::error::SlotsError;
+use crate::index::SlotIndex;
+use crate::iterator::{Iter, IterIndexed, IterIndexedMut, IterMut};
+
+/// Number of slots whose occupancy bits are stored in one bitmap word.
+pub(crate) const WORD_BITS: usize = u64::BITS as usize;
+
+/// Returns the number of bytes of a bitmap of `capacity` bits.
+pub(crate) const fn bitmap_len(capacity: usize) -> usize {
+    capacity.div_ceil(8)
+}
+
+/// Reads a word of a bitmap. The bits after the end of the bitmap are zero.
+pub(crate) fn load_word(bitmap: &[u8], word: usize) -> u64 {
+    let bytes = bitmap.get(word * 8..).unwrap_or(&[]);
+
+    match bytes.first_chunk() {
+        Some(&chunk) => u64::from_le_bytes(chunk),
+        None => {
+            let mut chunk = [0; 8];
+            chunk[..bytes.len()].copy_from_slice(bytes);
+            u64::from_le_bytes(chunk)
+        }
+    }
+}
+
+/// A single slot: the value while the slot is occupied, the link to the next released slot
+/// while it is free.
+///
+/// Which of the two is stored is tracked by the occupancy bitmap. The value is the first field of
+/// a `repr(C)` union, so it starts at the beginning of the entry.
+#[repr(C)]
+pub(crate) union Entry<IT, I: Copy> {
+    value: ManuallyDrop<MaybeUninit<IT>>,
+    next: I,
+}
+
+impl<IT, I: Copy> Entry<IT, I> {
+    /// An entry that holds neither a value nor a link.
+    pub(crate) const VACANT: Self = Self {
+        value: ManuallyDrop::new(MaybeUninit::uninit()),
+    };
+
+    fn value(&self) -> &MaybeUninit<IT> {
+        // Safety: a `MaybeUninit` is valid for any contents of the entry
+        unsafe { &self.value }
+    }
+
+    fn value_mut(&mut self) -> &mut MaybeUninit<IT> {
+        // Safety: a `MaybeUninit` is valid for any contents of the entry
+        unsafe { &mut self.value }
+    }
+}
+
+/// Memory that holds the slots of a collection.
+///
+/// The bitmap must be at least [`bitmap_len`] long for the number of entries. A value must only
+/// be considered initialized if its bit is set in the bitmap.
+pub(crate) trait Storage {
+    type Item;
+    type Index: SlotIndex;
+
+    fn parts(&self) -> (&[StorageEntry<Self>], &[u8]);
+
+    fn parts_mut(&mut self) -> (&mut [StorageEntry<Self>], &mut [u8]);
+}
+
+/// The entry type of a [`Storage`].
+type StorageEntry<S> = Entry<<S as Storage>::Item, <S as Storage>::Index>;
+
+/// Storage of a collection with a capacity known at compile time.
+pub(crate) struct ArrayStorage<IT, const N: usize, I: Copy> {
+    entries: [Entry<IT, I>; N],
+    bitmap: [u8; N],
+}
+
+impl<IT, const N: usize, I: SlotIndex> ArrayStorage<IT, N, I> {
+    pub(crate) const fn new() -> Self {
+        Self {
+            entries: [const { Entry::VACANT }; N],
+            bitmap: [0; N],
+        }
+    }
+}
+
+impl<IT, const N: usize, I: SlotIndex> Storage for ArrayStorage<IT, N, I> {
+    type Item = IT;
+    type Index = I;
+
+    fn parts(&self) -> (&[Entry<IT, I>], &[u8]) {
+        (&self.entries, &self.bitmap[..bitmap_len(N)])
+    }
+
+    fn parts_mut(&mut self) -> (&mut [Entry<IT, I>], &mut [u8]) {
+        (&mut self.entries, &mut self.bitmap[..bitmap_len(N)])
+    }
+}
+
+/// Storage of a collection that uses a buffer owned by someone else.
+pub(crate) struct BorrowedStorage<'a, IT, I: Copy> {
+    pub(crate) entries: &'a mut [Entry<IT, I>],
+    pub(crate) bitmap: &'a mut [u8],
+}
+
+impl<IT, I: SlotIndex> Storage for BorrowedStorage<'_, IT, I> {
+    type Item = IT;
+    type Index = I;
+
+    fn parts(&self) -> (&[Entry<IT, I>], &[u8]) {
+        (self.entries, self.bitmap)
+    }
+
+    fn parts_mut(&mut self) -> (&mut [Entry<IT, I>], &mut [u8]) {
+        (self.entries, self.bitmap)
+    }
+}
+
+/// Storage of a collection with a capacity chosen at runtime.
+#[cfg(feature = "alloc")]
+pub(crate) struct BoxStorage<IT, I: Copy> {
+    entries: Box<[Entry<IT, I>]>,
+    bitmap: Box<[u8]>,
+}
+
+#[cfg(feature = "alloc")]
+impl<IT, I: SlotIndex> BoxStorage<IT, I> {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            entries: (0..capacity).map(|_| Entry::VACANT).collect(),
+            bitmap: alloc::vec![0; bitmap_len(capacity)].into_boxed_slice(),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<IT, I: SlotIndex> Storage for BoxStorage<IT, I> {
+    type Item = IT;
+    type Index = I;
+
+    fn parts(&self) -> (&[Entry<IT, I>], &[u8]) {
+        (&self.entries, &self.bitmap)
+    }
+
+    fn parts_mut(&mut self) -> (&mut [Entry<IT, I>], &mut [u8]) {
+        (&mut self.entries, &mut self.bitmap)
+    }
+}
+
+/// Free slots that a collection being deserialized hands out first, in the order in which they
+/// were passed to [`RawSlots::place_released`].
+#[cfg(feature = "serde")]
+#[derive(Default)]
+pub(crate) struct ReleasedSlots {
+    first: Option<usize>,
+    last: Option<usize>,
+    len: usize,
+}
+
+/// Free slot stack over a [`Storage`].
+///
+/// The storage must only contain free slots when it is passed to [`RawSlots::new`], and its
+/// length must be representable by the index type.
+pub(crate) struct RawSlots<S: Storage> {
+    items: S,
+    /// Top of the stack of released slots. Only meaningful if the stack is not empty.
+    next_free: S::Index,
+    /// Number of slots that have been handed out at least once. These are the `touched` slots at
+    /// the end of the storage, slots below them have never been used and are allocated in
+    /// descending order once the stack of released slots is empty.
+    touched: S::Index,
+    count: S::Index,
+}
+
+impl<IT, const N: usize, I: SlotIndex> RawSlots<ArrayStorage<IT, N, I>> {
+    /// Initializes an empty collection in place, without creating a temporary array.
+    ///
+    /// # Safety
+    ///
+    /// `this` must be valid for writes and properly aligned. Any previous value is overwritten
+    /// without being dropped.
+    pub(crate) unsafe fn init_in_place(this: *mut Self) {
+        // the entries are left uninitialized, which is what a vacant entry holds, only the bitmap
+        // needs to be cleared
+        addr_of_mut!((*this).items.bitmap).write_bytes(0, 1);
+
+        addr_of_mut!((*this).next_free).write(I::ZERO);
+        addr_of_mut!((*this).touched).write(I::ZERO);
+        addr_of_mut!((*this).count).write(I::ZERO);
+    }
+}
+
+impl<S: Storage> RawSlots<S> {
+    pub(crate) const fn new(items: S) -> Self {
+        Self {
+            items,
+            next_free: S::Index::ZERO,
+            touched: S::Index::ZERO,
+            count: S::Index::ZERO,
+        }
+    }
+
+    pub(crate) fn iter(&self) -> Iter<'_, S::Item> {
+        Iter::from_indexed(self.iter_indexed())
+    }
+
+    pub(crate) fn iter_mut(&mut self) -> IterMut<'_, S::Item> {
+        IterMut::from_indexed(self.iter_indexed_mut())
+    }
+
+    pub(crate) fn iter_indexed(&self) -> IterIndexed<'_, S::Item> {
+        let count = self.count();
+        let (entries, bitmap) = self.items.parts();
+
+        // Safety: the bits of the bitmap mark initialized values, and there are `count` of them
+        unsafe { IterIndexed::from_parts(entries, bitmap, count) }
+    }
+
+    pub(crate) fn iter_indexed_mut(&mut self) -> IterIndexedMut<'_, S::Item> {
+        let count = self.count();
+        let (entries, bitmap) = self.items.parts_mut();
+
+        // Safety: the bits of the bitmap mark initialized values, and there are `count` of them
+        unsafe { IterIndexedMut::from_parts(entries, bitmap, count) }
+    }
+
+    pub(crate) fn capacity(&self) -> usize {
+        self.items.parts().0.len()
+    }
+
+    pub(crate) fn count(&self) -> usize {
+        self.count.to_usize()
+    }
+
+    pub(crate) fn is_full(&self) -> bool {
+        self.count() == self.capacity()
+    }
+
+    /// Drops every stored element and releases all slots.
+    ///
+    /// If a destructor panics, the elements that were not dropped yet are leaked, and the
+    /// collection is left empty.
+    pub(crate) fn clear(&mut self) {
+        /// Releases the slots that were not visited yet when a destructor unwinds.
+        struct Guard<'a> {
+            bitmap: &'a mut [u8],
+            next: usize,
+        }
+
+        impl Drop for Guard<'_> {
+            fn drop(&mut self) {
+                self.bitmap[self.next..].fill(0);
+            }
+        }
+
+        // every slot becomes untouched, so the stack of released slots is not read again
+        self.next_free = S::Index::ZERO;
+        self.touched = S::Index::ZERO;
+        self.count = S::Index::ZERO;
+
+        let (entries, bitmap) = self.items.parts_mut();
+        let mut guard = Guard { bitmap, next: 0 };
+
+        while let Some(byte) = guard.bitmap.get_mut(guard.next) {
+            // release the slots of the byte before dropping their values, so none of them is
+            // dropped twice
+            let mut bits = core::mem::take(byte);
+            let first = guard.next * 8;
+            guard.next += 1;
+
+            while bits != 0 {
+                let idx = first + bits.trailing_zeros() as usize;
+                bits &= bits - 1;
+
+                // Safety: the value of an occupied slot is initialized
+                unsafe { entries[idx].value_mut().assume_init_drop() };
+            }
+        }
+    }
+
+    fn words(&self) -> usize {
+        self.capacity().div_ceil(WORD_BITS)
+    }
+
+    fn load_word(&self, word: usize) -> u64 {
+        load_word(self.items.parts().1, word)
+    }
+
+    fn set_occupied(&mut self, idx: usize, occupied: bool) {
+        let byte = &mut self.items.parts_mut().1[idx / 8];
+        let bit = 1 << (idx % 8);
+
+        if occupied {
+            *byte |= bit;
+        } else {
+            *byte &= !bit;
+        }
+    }
+
+    fn has_released_slots(&self) -> bool {
+        // every touched slot is either occupied or released
+        self.count != self.touched
+    }
+
+    fn is_occupied(&self, idx: usize) -> Option<bool> {
+        if idx < self.capacity() {
+            Some(self.items.parts().1[idx / 8] & (1 << (idx % 8)) != 0)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the link of a released slot.
+    fn next_of(&self, idx: usize) -> S::Index {
+        // Safety: the links of released slots are always written before the slot is pushed on the
+        // stack, and the entry is not occupied so the link is not overwritten by a value
+        unsafe { self.items.parts().0[idx].next }
+    }
+
+    fn set_next(&mut self, idx: usize, next: S::Index) {
+        self.items.parts_mut().0[idx].next = next;
+    }
+
+    /// Returns the released slots, from the top of the stack to the bottom.
+    ///
+    /// Storing elements reuses these slots first, in this order, before the slots that were never
+    /// used.
+    #[cfg(feature = "serde")]
+    pub(crate) fn released(&self) -> impl Iterator<Item = usize> + '_ {
+        let len = self.touched.to_usize() - self.count();
+
+        core::iter::successors(Some(self.next_free.to_usize()), |&idx| {
+            Some(self.next_of(idx).to_usize())
+        })
+        .take(len)
+    }
+
+    /// Places an element in a particular slot, without updating the stack of released slots.
+    ///
+    /// Returns the element if the slot is occupied, listed in `released`, or outside the
+    /// collection. [`rebuild_free_list`](RawSlots::rebuild_free_list) must be called after the
+    /// elements are placed.
+    #[cfg(feature = "serde")]
+    pub(crate) fn place(&mut self, idx: usize, item: S::Item) -> Result<(), S::Item> {
+        if self.is_occupied(idx) != Some(false) {
+            return Err(item);
+        }
+
+        self.items.parts_mut().0[idx].value_mut().write(item);
+        self.set_occupied(idx, true);
+
+        Ok(())
+    }
+
+    /// Appends a free slot to the slots that the rebuilt collection hands out first.
+    ///
+    /// Slots can be listed before or after the elements are placed. Until
+    /// [`rebuild_free_list`](RawSlots::rebuild_free_list) is called, a listed slot is marked as
+    /// occupied, so it is rejected if it is listed twice or if an element is placed in it.
+    /// Returns an error if the slot is occupied, listed already, or outside the collection.
+    #[cfg(feature = "serde")]
+    pub(crate) fn place_released(
+        &mut self,
+        released: &mut ReleasedSlots,
+        idx: usize,
+    ) -> Result<(), ()> {
+        if self.is_occupied(idx) != Some(false) {
+            return Err(());
+        }
+
+        self.set_occupied(idx, true);
+        match released.last {
+            Some(last) => self.set_next(last, S::Index::from_usize(idx)),
+            None => released.first = Some(idx),
+        }
+        released.last = Some(idx);
+        released.len += 1;
+
+        Ok(())
+    }
+
+    /// Rebuilds the stack of released slots and the element count after the elements were placed.
+    ///
+    /// The `released` slots are handed out first, in the order in which they were listed. They
+    /// are followed by the other free slots, highest index first. Free slots below every occupied
+    /// and every listed slot are considered never used, so a collection hands out its free slots
+    /// in the same order after a round-trip, as long as its released slots are listed.
+    #[cfg(feature = "serde")]
+    pub(crate) fn rebuild_free_list(&mut self, released: ReleasedSlots) {
+        let capacity = self.capacity();
+
+        // the listed slots are still marked as occupied, so the free slots below the lowest
+        // marked one are exactly the ones that were never used
+        let lowest = (0..self.words())
+            .find_map(|word| match self.load_word(word) {
+                0 => None,
+                bits => Some(word * WORD_BITS + bits.trailing_zeros() as usize),
+            })
+            .unwrap_or(capacity);
+
+        let mut last = released.last;
+        let mut appended = 0;
+        for idx in (lowest..capacity).rev() {
+            if self.is_occupied(idx) == Some(false) {
+                match last {
+                    Some(last) => self.set_next(last, S::Index::from_usize(idx)),
+                    None => self.next_free = S::Index::from_usize(idx),
+                }
+                last = Some(idx);
+                appended += 1;
+            }
+        }
+
+        if let Some(first) = released.first {
+            self.next_free = S::Index::from_usize(first);
+        }
+        if let Some(last) = last {
+            // the bottom of the stack is never followed, but the link must be initialized
+            self.set_next(last, S::Index::ZERO);
+        }
+
+        // unmark the listed slots
+        let mut idx = self.next_free.to_usize();
+        for _ in 0..released.len {
+            self.set_occupied(idx, false);
+            idx = self.next_of(idx).to_usize();
+        }
+
+        let count: usize = (0..self.words())
+            .map(|word| self.load_word(word).count_ones() as usize)
+            .sum();
+        self.count = S::Index::from_usize(count);
+        self.touched = S::Index::from_usize(capacity - lowest);
+        debug_assert_eq!(
+            self.touched.to_usize(),
+            self.count() + released.len + appended
+        );
+    }
+
+    /// Pushes a slot on the stack of released slots. The value must already be moved out.
+    fn free(&mut self, idx: usize) {
+        debug_assert!(self.count() != 0, "Free called on an empty collection");
+
+        // the bottom of the stack is never followed, but its link must still be initialized
+        let next = if self.has_released_slots() {
+            self.next_free
+        } else {
+            S::Index::ZERO
+        };
+        self.set_next(idx, next);
+        self.set_occupied(idx, false);
+
+        self.next_free = S::Index::from_usize(idx); // the freed element will always be the top of the free stack
+        self.count = S::Index::from_usize(self.count() - 1);
+    }
+
+    /// Returns the slot that the next [`store`](RawSlots::store) will use, without allocating it.
+    pub(crate) fn peek_free(&self) -> Option<usize> {
+        if self.has_released_slots() {
+            // next_free points to the top of the free stack
+            Some(self.next_free.to_usize())
+        } else if self.touched.to_usize() < self.capacity() {
+            // the highest slot that was never used
+            Some(self.capacity() - self.touched.to_usize() - 1)
+        } else {
+            // no free slot
+            None
+        }
+    }
+
+    fn alloc(&mut self) -> Option<usize> {
+        let index = self.peek_free()?;
+
+        if self.has_released_slots() {
+            self.next_free = self.next_of(index); // pop the stack
+        } else {
+            self.touched = S::Index::from_usize(self.touched.to_usize() + 1);
+        }
+
+        self.set_occupied(index, true);
+        self.count = S::Index::from_usize(self.count() + 1);
+        Some(index)
+    }
+
+    pub(crate) fn store(&mut self, item: S::Item) -> Result<usize, S::Item> {
+        self.store_mut(item).map(|(i, _)| i)
+    }
+
+    /// Stores an element like [`store`](RawSlots::store), and also returns a reference to it.
+    pub(crate) fn store_mut(&mut self, item: S::Item) -> Result<(usize, &mut S::Item), S::Item> {
+        match self.alloc() {
+            Some(i) => Ok((i, self.items.parts_mut().0[i].value_mut().write(item))),
+            None => Err(item),
+        }
+    }
+
+    pub(crate) fn take(&mut self, key: usize) -> Result<S::Item, SlotsError<S::Item>> {
+        self.get(key)?;
+
+        // Safety: the slot is occupied, and it is released below so the value is not read again
+        let item = unsafe { self.items.parts().0[key].value().assume_init_read() };
+        self.free(key);
+
+        Ok(item)
+    }
+
+    /// Returns the index of the first occupied slot at or after `from`.
+    pub(crate) fn next_occupied(&self, from: usize) -> Option<usize> {
+        if self.count() == 0 || from >= self.capacity() {
+            return None;
+        }
+
+        let mut word = from / WORD_BITS;
+        // ignore the slots before `from`
+        let mut bits = self.load_word(word) & (u64::MAX << (from % WORD_BITS));
+        while bits == 0 {
+            word += 1;
+            if word >= self.words() {
+                return None;
+            }
+            bits = self.load_word(word);
+        }
+
+        Some(word * WORD_BITS + bits.trailing_zeros() as usize)
+    }
+
+    /// Takes out the first element at or after `*cursor`, and moves the cursor after it.
+    ///
+    /// The element is released like in [`take`](RawSlots::take), so the collection remains
+    /// consistent if the caller stops early.
+    pub(crate) fn take_next(&mut self, cursor: &mut usize) -> Option<(usize, S::Item)> {
+        let idx = self.next_occupied(*cursor)?;
+        *cursor = idx + 1;
+
+        self.take(idx).ok().map(|item| (idx, item))
+    }
+
+    pub(crate) fn get(&self, key: usize) -> Result<&S::Item, SlotsError<S::Item>> {
+        match self.is_occupied(key) {
+            // Safety: the value of an occupied slot is initialized
+            Some(true) => Ok(unsafe { self.items.parts().0[key].value().assume_init_ref() }),
+            Some(false) => Err(SlotsError::Vacant),
+            None => Err(SlotsError::OutOfBounds),
+        }
+    }
+
+    pub(crate) fn get_mut(&mut self, key: usize) -> Result<&mut S::Item, SlotsError<S::Item>> {
+        match self.is_occupied(key) {
+            // Safety: the value of an occupied slot is initialized
+            Some(true) => {
+                Ok(unsafe { self.items.parts_mut().0[key].value_mut().assume_init_mut() })
+            }
+            Some(false) => Err(SlotsError::Vacant),
+            None => Err(SlotsError::OutOfBounds),
+        }
+    }
+
+    pub(crate) fn get_many_mut<const M: usize>(
+        &mut self,
+        keys: [usize; M],
+    ) -> Option<[&mut S::Item; M]> {
+        if !keys.iter().all(|&key| self.is_occupied(key) == Some(true)) {
+            return None;
+        }
+
+        let entries = self.items.parts_mut().0.get_disjoint_mut(keys).ok()?;
+
+        // Safety: every slot was checked to be occupied above
+        Some(entries.map(|entry| unsafe { entry.value_mut().assume_init_mut() }))
+    }
+}
+
+impl<S: Storage> Drop for RawSlots<S> {
+    fn drop(&mut self) {
+        if needs_drop::<S::Item>() {
+            self.clear();
+        }
+    }
+}
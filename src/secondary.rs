@@ -0,0 +1,529 @@
+//! Collections that attach extra data to the elements of another collection.
+//!
+//! A secondary collection stores values under the keys or handles returned by a _primary_
+//! collection, for example to attach a debug name to some of the elements of a [`Slots`] object.
+//! Values are addressed by the slot index of the key, so a secondary collection is as fast to
+//! access as the primary one.
+//!
+//!  * [`SecondarySlots`] reserves a value for every slot of the primary collection.
+//!  * [`SparseSecondarySlots`] only allocates memory for the values that are actually stored.
+//!    It is only available when the `alloc` feature is enabled.
+//!
+//! Any type that implements [`SecondaryKey`] can be used as a key: `usize` indices returned by
+//! [`UnrestrictedSlots`] and [`DenseSlots`], references to [`Slots`] keys, and [`Handle`]s of
+//! [`GenerationalSlots`].
+//!
+//! ```rust
+//! use slots::secondary::SecondarySlots;
+//! use slots::slots::Slots;
+//!
+//! let mut entities: Slots<_, 4> = Slots::new();
+//! let mut names: SecondarySlots<&str, 4> = SecondarySlots::new();
+//!
+//! let player = entities.store(100).unwrap();
+//! let enemy = entities.store(50).unwrap();
+//!
+//! names.insert(&player, "player");
+//!
+//! assert_eq!(Some(&"player"), names.get(&player));
+//! assert_eq!(None, names.get(&enemy));
+//!
+//! // visit the elements that have a name
+//! for (health, name) in names.join(&entities) {
+//!     assert_eq!((&100, &"player"), (health, name));
+//! }
+//! ```
+//!
+//! # Stale entries
+//!
+//! A secondary collection can't know when an element is taken out of the primary collection, so
+//! its value is kept until it is removed or overwritten. When the keys carry a generation, like
+//! [`Handle`]s and [`Slots`] keys do, values are stored together with the generation of their key,
+//! and values of earlier elements of the same slot are not returned.
+//!
+//! ```rust
+//! use slots::generational::GenerationalSlots;
+//! use slots::secondary::SecondarySlots;
+//!
+//! let mut entities: GenerationalSlots<_, 4> = GenerationalSlots::new();
+//! let mut names: SecondarySlots<&str, 4> = SecondarySlots::new();
+//!
+//! let old = entities.store(1).unwrap();
+//! names.insert(old, "old");
+//! entities.take(old);
+//!
+//! // the slot is reused
+//! let new = entities.store(2).unwrap();
+//! assert_eq!(old.index(), new.index());
+//!
+//! assert_eq!(None, names.get(new));
+//! assert_eq!(0, names.join(&entities).count());
+//! ```
+//!
+//! Keys without a generation can't detect stale values, so values should be removed when the
+//! element is taken out of the primary collection.
+//!
+//! [`Slots`]: crate::slots::Slots
+//! [`UnrestrictedSlots`]: crate::unrestricted::UnrestrictedSlots
+//! [`DenseSlots`]: crate::dense::DenseSlots
+//! [`GenerationalSlots`]: crate::generational::GenerationalSlots
+
+#[cfg(feature = "alloc")]
+use alloc::collections::btree_map::{self, BTreeMap};
+use core::iter::Enumerate;
+use core::slice;
+
+use crate::dense::DenseSlots;
+use crate::generational::{GenerationalSlots, Handle};
+use crate::index::SlotIndex;
+use crate::owner::OwnerIdSource;
+use crate::slots::{Key, Slots};
+use crate::unrestricted::UnrestrictedSlots;
+
+/// A key that addresses an element of a primary collection.
+pub trait SecondaryKey {
+    /// Returns the index of the slot that holds the element.
+    fn index(&self) -> usize;
+
+    /// Returns the generation of the element. Keys that can't detect stale elements return 0.
+    ///
+    /// Generations are widened to `u32`, which can hold the generation of every index type.
+    fn generation(&self) -> u32 {
+        0
+    }
+}
+
+fn widen<G: SlotIndex>(generation: G) -> u32 {
+    generation.to_usize() as u32
+}
+
+impl SecondaryKey for usize {
+    fn index(&self) -> usize {
+        *self
+    }
+}
+
+impl<IT, const N: usize, I: SlotIndex, O: OwnerIdSource> SecondaryKey for &Key<IT, N, I, O> {
+    fn index(&self) -> usize {
+        Key::index(self)
+    }
+
+    fn generation(&self) -> u32 {
+        widen(Key::raw(self).generation())
+    }
+}
+
+impl<I: SlotIndex> SecondaryKey for Handle<I> {
+    fn index(&self) -> usize {
+        Handle::index(self)
+    }
+
+    fn generation(&self) -> u32 {
+        widen(Handle::generation(self))
+    }
+}
+
+/// A collection that secondary collections can be joined with.
+pub trait Primary {
+    /// The type of the stored elements.
+    type Item;
+
+    /// Returns the element stored in slot `index`, if it belongs to `generation`.
+    ///
+    /// Collections that don't track generations ignore `generation`.
+    fn lookup(&self, index: usize, generation: u32) -> Option<&Self::Item>;
+}
+
+impl<IT, const N: usize, I: SlotIndex, O: OwnerIdSource> Primary for Slots<IT, N, I, O> {
+    type Item = IT;
+
+    fn lookup(&self, index: usize, generation: u32) -> Option<&IT> {
+        if self.generation(index).map(widen) == Some(generation) {
+            self.try_get(index)
+        } else {
+            None
+        }
+    }
+}
+
+impl<IT, const N: usize, I: SlotIndex> Primary for UnrestrictedSlots<IT, N, I> {
+    type Item = IT;
+
+    fn lookup(&self, index: usize, _generation: u32) -> Option<&IT> {
+        self.get(index)
+    }
+}
+
+impl<IT, const N: usize, I: SlotIndex> Primary for DenseSlots<IT, N, I> {
+    type Item = IT;
+
+    fn lookup(&self, index: usize, _generation: u32) -> Option<&IT> {
+        self.get(index)
+    }
+}
+
+impl<IT, const N: usize, I: SlotIndex> Primary for GenerationalSlots<IT, N, I> {
+    type Item = IT;
+
+    fn lookup(&self, index: usize, generation: u32) -> Option<&IT> {
+        let generation = generation as usize;
+        if index < N && generation <= I::Generation::MAX_CAPACITY {
+            self.get(Handle::from_parts(
+                I::from_usize(index),
+                I::Generation::from_usize(generation),
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+/// A stored value, and the generation of the key it was stored with.
+struct Entry<V> {
+    generation: u32,
+    value: V,
+}
+
+impl<V> Entry<V> {
+    fn get(&self, generation: u32) -> Option<&V> {
+        (self.generation == generation).then_some(&self.value)
+    }
+
+    fn get_mut(&mut self, generation: u32) -> Option<&mut V> {
+        (self.generation == generation).then_some(&mut self.value)
+    }
+}
+
+/// Replaces the value of a slot. Returns the previous value if it belongs to the same element.
+fn replace<V>(slot: &mut Option<Entry<V>>, generation: u32, value: V) -> Option<V> {
+    slot.replace(Entry { generation, value })
+        .filter(|entry| entry.generation == generation)
+        .map(|entry| entry.value)
+}
+
+/// Removes the value of a slot, if it belongs to the given generation.
+fn remove<V>(slot: &mut Option<Entry<V>>, generation: u32) -> Option<V> {
+    if slot.as_ref()?.generation == generation {
+        slot.take().map(|entry| entry.value)
+    } else {
+        None
+    }
+}
+
+/// Secondary collection that reserves a value for every slot of the primary collection.
+///
+/// The struct has two type parameters:
+///  - `V` is the type of the stored values
+///  - `N` is the number of slots. It should match the capacity of the primary collection.
+///
+/// For more information, see the [module level documentation](crate::secondary)
+pub struct SecondarySlots<V, const N: usize> {
+    entries: [Option<Entry<V>>; N],
+    count: usize,
+}
+
+impl<V, const N: usize> Default for SecondarySlots<V, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V, const N: usize> SecondarySlots<V, N> {
+    /// Creates a new, empty SecondarySlots object.
+    ///
+    /// This function can be used in const context, for example to initialize a `static`.
+    pub const fn new() -> Self {
+        Self {
+            entries: [const { None }; N],
+            count: 0,
+        }
+    }
+
+    /// Returns the number of slots
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns the number of stored values, including stale ones.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Stores a value for the element that belongs to the key.
+    ///
+    /// Returns the value that was previously stored for the same element. Values of earlier
+    /// elements of the same slot are dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the index of the key is outside the collection.
+    ///
+    /// ```
+    /// # use slots::secondary::SecondarySlots;
+    /// let mut names: SecondarySlots<_, 4> = SecondarySlots::new();
+    ///
+    /// assert_eq!(None, names.insert(2, "first"));
+    /// assert_eq!(Some("first"), names.insert(2, "second"));
+    /// ```
+    pub fn insert(&mut self, key: impl SecondaryKey, value: V) -> Option<V> {
+        let slot = self.entries.get_mut(key.index()).expect("Invalid key");
+        if slot.is_none() {
+            self.count += 1;
+        }
+
+        replace(slot, key.generation(), value)
+    }
+
+    /// Removes and returns the value that belongs to the key.
+    ///
+    /// ```
+    /// # use slots::secondary::SecondarySlots;
+    /// let mut names: SecondarySlots<_, 4> = SecondarySlots::new();
+    /// names.insert(2, "name");
+    ///
+    /// assert_eq!(Some("name"), names.remove(2));
+    /// assert_eq!(None, names.remove(2));
+    /// ```
+    pub fn remove(&mut self, key: impl SecondaryKey) -> Option<V> {
+        let value = remove(self.entries.get_mut(key.index())?, key.generation())?;
+        self.count -= 1;
+
+        Some(value)
+    }
+
+    /// Returns whether a value is stored for the element that belongs to the key.
+    pub fn contains_key(&self, key: impl SecondaryKey) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Returns a reference to the value that belongs to the key.
+    pub fn get(&self, key: impl SecondaryKey) -> Option<&V> {
+        self.entries
+            .get(key.index())?
+            .as_ref()?
+            .get(key.generation())
+    }
+
+    /// Returns a mutable reference to the value that belongs to the key.
+    pub fn get_mut(&mut self, key: impl SecondaryKey) -> Option<&mut V> {
+        self.entries
+            .get_mut(key.index())?
+            .as_mut()?
+            .get_mut(key.generation())
+    }
+
+    /// Returns an iterator over the slot indices and the values stored for them, including
+    /// stale values.
+    pub fn iter(&self) -> Iter<'_, V> {
+        Iter {
+            inner: self.entries.iter().enumerate(),
+        }
+    }
+
+    /// Returns an iterator over the elements of `primary` that have a value in this collection,
+    /// together with their values.
+    ///
+    /// Stale values are skipped if the keys carry a generation.
+    ///
+    /// ```
+    /// # use slots::secondary::SecondarySlots;
+    /// # use slots::unrestricted::UnrestrictedSlots;
+    /// let mut primary: UnrestrictedSlots<_, 4> = UnrestrictedSlots::new();
+    /// let mut secondary: SecondarySlots<_, 4> = SecondarySlots::new();
+    ///
+    /// let k1 = primary.store(1).unwrap();
+    /// let k2 = primary.store(2).unwrap();
+    /// secondary.insert(k1, "one");
+    /// secondary.insert(k2, "two");
+    ///
+    /// primary.take(k2);
+    ///
+    /// let joined: Vec<_> = secondary.join(&primary).collect();
+    /// assert_eq!(vec![(&1, &"one")], joined);
+    /// ```
+    pub fn join<'a, P: Primary>(&'a self, primary: &'a P) -> Join<'a, P, V> {
+        Join {
+            primary,
+            inner: self.entries.iter().enumerate(),
+        }
+    }
+}
+
+/// Iterator over the values of a [`SecondarySlots`] object.
+pub struct Iter<'a, V> {
+    inner: Enumerate<slice::Iter<'a, Option<Entry<V>>>>,
+}
+
+impl<'a, V> Iterator for Iter<'a, V> {
+    type Item = (usize, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner
+            .by_ref()
+            .find_map(|(index, entry)| entry.as_ref().map(|entry| (index, &entry.value)))
+    }
+}
+
+/// Iterator over the elements of a primary collection and the values of a [`SecondarySlots`]
+/// object.
+pub struct Join<'a, P, V> {
+    primary: &'a P,
+    inner: Enumerate<slice::Iter<'a, Option<Entry<V>>>>,
+}
+
+impl<'a, P: Primary, V> Iterator for Join<'a, P, V> {
+    type Item = (&'a P::Item, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let primary = self.primary;
+
+        self.inner.by_ref().find_map(|(index, entry)| {
+            let entry = entry.as_ref()?;
+            let item = primary.lookup(index, entry.generation)?;
+
+            Some((item, &entry.value))
+        })
+    }
+}
+
+/// Secondary collection that only allocates memory for the stored values.
+///
+/// The struct has one type parameter:
+///  - `V` is the type of the stored values
+///
+/// This type is only available when the `alloc` feature is enabled.
+///
+/// For more information, see the [module level documentation](crate::secondary)
+#[cfg(feature = "alloc")]
+pub struct SparseSecondarySlots<V> {
+    entries: BTreeMap<usize, Entry<V>>,
+}
+
+#[cfg(feature = "alloc")]
+impl<V> Default for SparseSecondarySlots<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<V> SparseSecondarySlots<V> {
+    /// Creates a new, empty SparseSecondarySlots object.
+    pub const fn new() -> Self {
+        Self {
+            entries: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the number of stored values, including stale ones.
+    pub fn count(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Stores a value for the element that belongs to the key.
+    ///
+    /// Returns the value that was previously stored for the same element. Values of earlier
+    /// elements of the same slot are dropped.
+    ///
+    /// ```
+    /// # use slots::secondary::SparseSecondarySlots;
+    /// let mut names = SparseSecondarySlots::new();
+    ///
+    /// assert_eq!(None, names.insert(2, "first"));
+    /// assert_eq!(Some("first"), names.insert(2, "second"));
+    /// ```
+    pub fn insert(&mut self, key: impl SecondaryKey, value: V) -> Option<V> {
+        let generation = key.generation();
+
+        self.entries
+            .insert(key.index(), Entry { generation, value })
+            .filter(|entry| entry.generation == generation)
+            .map(|entry| entry.value)
+    }
+
+    /// Removes and returns the value that belongs to the key.
+    pub fn remove(&mut self, key: impl SecondaryKey) -> Option<V> {
+        match self.entries.entry(key.index()) {
+            btree_map::Entry::Occupied(entry) if entry.get().generation == key.generation() => {
+                Some(entry.remove().value)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns whether a value is stored for the element that belongs to the key.
+    pub fn contains_key(&self, key: impl SecondaryKey) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Returns a reference to the value that belongs to the key.
+    pub fn get(&self, key: impl SecondaryKey) -> Option<&V> {
+        self.entries.get(&key.index())?.get(key.generation())
+    }
+
+    /// Returns a mutable reference to the value that belongs to the key.
+    pub fn get_mut(&mut self, key: impl SecondaryKey) -> Option<&mut V> {
+        self.entries
+            .get_mut(&key.index())?
+            .get_mut(key.generation())
+    }
+
+    /// Returns an iterator over the slot indices and the values stored for them, including
+    /// stale values.
+    pub fn iter(&self) -> SparseIter<'_, V> {
+        SparseIter {
+            inner: self.entries.iter(),
+        }
+    }
+
+    /// Returns an iterator over the elements of `primary` that have a value in this collection,
+    /// together with their values.
+    ///
+    /// Stale values are skipped if the keys carry a generation.
+    pub fn join<'a, P: Primary>(&'a self, primary: &'a P) -> SparseJoin<'a, P, V> {
+        SparseJoin {
+            primary,
+            inner: self.entries.iter(),
+        }
+    }
+}
+
+/// Iterator over the values of a [`SparseSecondarySlots`] object.
+#[cfg(feature = "alloc")]
+pub struct SparseIter<'a, V> {
+    inner: btree_map::Iter<'a, usize, Entry<V>>,
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, V> Iterator for SparseIter<'a, V> {
+    type Item = (usize, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next()
+            .map(|(&index, entry)| (index, &entry.value))
+    }
+}
+
+/// Iterator over the elements of a primary collection and the values of a
+/// [`SparseSecondarySlots`] object.
+#[cfg(feature = "alloc")]
+pub struct SparseJoin<'a, P, V> {
+    primary: &'a P,
+    inner: btree_map::Iter<'a, usize, Entry<V>>,
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, P: Primary, V> Iterator for SparseJoin<'a, P, V> {
+    type Item = (&'a P::Item, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let primary = self.primary;
+
+        self.inner.by_ref().find_map(|(&index, entry)| {
+            let item = primary.lookup(index, entry.generation)?;
+
+            Some((item, &entry.value))
+        })
+    }
+}
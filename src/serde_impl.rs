@@ -0,0 +1,516 @@
+//! `Serialize` and `Deserialize` implementations, enabled by the `serde` feature.
+//!
+//! See the crate level documentation for the serialized format.
+
+use core::fmt;
+use core::marker::PhantomData;
+
+use serde::de::{self, Deserialize, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeStruct, SerializeTuple, Serializer};
+
+use crate::generational::{GenerationalSlots, Handle};
+use crate::index::SlotIndex;
+use crate::owner::OwnerIdSource;
+use crate::raw::ReleasedSlots;
+use crate::slots::Slots;
+use crate::unrestricted::UnrestrictedSlots;
+
+/// Serializes the occupied slots of a collection as `(index, element)` pairs.
+struct Items<'a, IT, const N: usize, I: SlotIndex>(&'a UnrestrictedSlots<IT, N, I>);
+
+impl<IT: Serialize, const N: usize, I: SlotIndex> Serialize for Items<'_, IT, N, I> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.iter_indexed())
+    }
+}
+
+/// Serializes the released slots of a collection, in the order in which they are reused.
+struct Released<'a, IT, const N: usize, I: SlotIndex>(&'a UnrestrictedSlots<IT, N, I>);
+
+impl<IT, const N: usize, I: SlotIndex> Serialize for Released<'_, IT, N, I> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.released())
+    }
+}
+
+impl<IT: Serialize, const N: usize, I: SlotIndex> Serialize for UnrestrictedSlots<IT, N, I> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("UnrestrictedSlots", 2)?;
+        state.serialize_field("items", &Items(self))?;
+        state.serialize_field("free", &Released(self))?;
+        state.end()
+    }
+}
+
+/// Places the deserialized `(index, element)` pairs into a collection.
+struct ItemsSeed<'a, IT, const N: usize, I: SlotIndex>(&'a mut UnrestrictedSlots<IT, N, I>);
+
+impl<'de, IT: Deserialize<'de>, const N: usize, I: SlotIndex> DeserializeSeed<'de>
+    for ItemsSeed<'_, IT, N, I>
+{
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, IT: Deserialize<'de>, const N: usize, I: SlotIndex> Visitor<'de>
+    for ItemsSeed<'_, IT, N, I>
+{
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            formatter,
+            "a sequence of at most {N} (index, element) pairs"
+        )
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        while let Some((idx, item)) = seq.next_element::<(usize, IT)>()? {
+            if self.0.place(idx, item).is_err() {
+                return Err(de::Error::invalid_value(
+                    de::Unexpected::Unsigned(idx as u64),
+                    &"a unique index smaller than the capacity",
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Lists the deserialized indices as the released slots of a collection.
+struct ReleasedSeed<'a, IT, const N: usize, I: SlotIndex> {
+    slots: &'a mut UnrestrictedSlots<IT, N, I>,
+    released: &'a mut ReleasedSlots,
+}
+
+impl<'de, IT, const N: usize, I: SlotIndex> DeserializeSeed<'de> for ReleasedSeed<'_, IT, N, I> {
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, IT, const N: usize, I: SlotIndex> Visitor<'de> for ReleasedSeed<'_, IT, N, I> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a sequence of at most {N} free slot indices")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        while let Some(idx) = seq.next_element::<usize>()? {
+            if self.slots.place_released(self.released, idx).is_err() {
+                return Err(de::Error::invalid_value(
+                    de::Unexpected::Unsigned(idx as u64),
+                    &"the unique index of a free slot",
+                ));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+const UNRESTRICTED_FIELDS: &[&str] = &["items", "free"];
+
+enum UnrestrictedField {
+    Items,
+    Free,
+}
+
+impl<'de> Deserialize<'de> for UnrestrictedField {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FieldVisitor;
+
+        impl Visitor<'_> for FieldVisitor {
+            type Value = UnrestrictedField;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("`items` or `free`")
+            }
+
+            fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+                match value {
+                    0 => Ok(UnrestrictedField::Items),
+                    1 => Ok(UnrestrictedField::Free),
+                    _ => Err(de::Error::invalid_value(
+                        de::Unexpected::Unsigned(value),
+                        &self,
+                    )),
+                }
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                match value {
+                    "items" => Ok(UnrestrictedField::Items),
+                    "free" => Ok(UnrestrictedField::Free),
+                    _ => Err(de::Error::unknown_field(value, UNRESTRICTED_FIELDS)),
+                }
+            }
+        }
+
+        deserializer.deserialize_identifier(FieldVisitor)
+    }
+}
+
+struct UnrestrictedVisitor<IT, const N: usize, I>(PhantomData<(IT, I)>);
+
+impl<'de, IT: Deserialize<'de>, const N: usize, I: SlotIndex> Visitor<'de>
+    for UnrestrictedVisitor<IT, N, I>
+{
+    type Value = UnrestrictedSlots<IT, N, I>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("struct UnrestrictedSlots")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut slots = UnrestrictedSlots::new();
+        let mut released = ReleasedSlots::default();
+
+        seq.next_element_seed(ItemsSeed(&mut slots))?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        seq.next_element_seed(ReleasedSeed {
+            slots: &mut slots,
+            released: &mut released,
+        })?
+        .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+
+        slots.rebuild_free_list(released);
+
+        Ok(slots)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut slots = UnrestrictedSlots::new();
+        let mut released = ReleasedSlots::default();
+        let mut items = false;
+        let mut free = false;
+
+        // the free slots are marked while they are listed, so the fields can come in any order
+        while let Some(field) = map.next_key()? {
+            match field {
+                UnrestrictedField::Items => {
+                    if core::mem::replace(&mut items, true) {
+                        return Err(de::Error::duplicate_field("items"));
+                    }
+                    map.next_value_seed(ItemsSeed(&mut slots))?;
+                }
+                UnrestrictedField::Free => {
+                    if core::mem::replace(&mut free, true) {
+                        return Err(de::Error::duplicate_field("free"));
+                    }
+                    map.next_value_seed(ReleasedSeed {
+                        slots: &mut slots,
+                        released: &mut released,
+                    })?;
+                }
+            }
+        }
+
+        if !items {
+            return Err(de::Error::missing_field("items"));
+        }
+        if !free {
+            return Err(de::Error::missing_field("free"));
+        }
+
+        slots.rebuild_free_list(released);
+
+        Ok(slots)
+    }
+}
+
+impl<'de, IT: Deserialize<'de>, const N: usize, I: SlotIndex> Deserialize<'de>
+    for UnrestrictedSlots<IT, N, I>
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_struct(
+            "UnrestrictedSlots",
+            UNRESTRICTED_FIELDS,
+            UnrestrictedVisitor(PhantomData),
+        )
+    }
+}
+
+impl<I: SlotIndex> Serialize for Handle<I> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut tuple = serializer.serialize_tuple(2)?;
+        tuple.serialize_element(&self.index())?;
+        tuple.serialize_element(&self.generation().to_usize())?;
+        tuple.end()
+    }
+}
+
+impl<'de, I: SlotIndex> Deserialize<'de> for Handle<I> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let (index, generation) = <(usize, usize)>::deserialize(deserializer)?;
+
+        if index >= I::MAX_CAPACITY {
+            return Err(de::Error::invalid_value(
+                de::Unexpected::Unsigned(index as u64),
+                &"an index that fits the index type",
+            ));
+        }
+        if generation > I::Generation::MAX_CAPACITY {
+            return Err(de::Error::invalid_value(
+                de::Unexpected::Unsigned(generation as u64),
+                &"a generation that fits the generation type",
+            ));
+        }
+
+        Ok(Handle::from_parts(
+            I::from_usize(index),
+            I::Generation::from_usize(generation),
+        ))
+    }
+}
+
+/// Serializes the generation of every slot.
+struct Generations<'a, G: SlotIndex>(&'a [G]);
+
+impl<G: SlotIndex> Serialize for Generations<'_, G> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.iter().map(|generation| generation.to_usize()))
+    }
+}
+
+/// Deserializes exactly `N` generations.
+struct GenerationsArray<G, const N: usize>([G; N]);
+
+impl<'de, G: SlotIndex, const N: usize> Deserialize<'de> for GenerationsArray<G, N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct GenerationsVisitor<G, const N: usize>(PhantomData<G>);
+
+        impl<'de, G: SlotIndex, const N: usize> Visitor<'de> for GenerationsVisitor<G, N> {
+            type Value = GenerationsArray<G, N>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                write!(formatter, "a sequence of {N} generations")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut generations = [G::ZERO; N];
+
+                for (idx, generation) in generations.iter_mut().enumerate() {
+                    let value: usize = seq
+                        .next_element()?
+                        .ok_or_else(|| de::Error::invalid_length(idx, &self))?;
+                    if value > G::MAX_CAPACITY {
+                        return Err(de::Error::invalid_value(
+                            de::Unexpected::Unsigned(value as u64),
+                            &"a generation that fits the generation type",
+                        ));
+                    }
+                    *generation = G::from_usize(value);
+                }
+
+                if seq.next_element::<de::IgnoredAny>()?.is_some() {
+                    return Err(de::Error::invalid_length(N + 1, &self));
+                }
+
+                Ok(GenerationsArray(generations))
+            }
+        }
+
+        deserializer.deserialize_seq(GenerationsVisitor(PhantomData))
+    }
+}
+
+/// Collections that are serialized as the generation of every slot and an [`UnrestrictedSlots`].
+trait WithGenerations<IT, const N: usize, I: SlotIndex>: Sized {
+    const NAME: &'static str;
+
+    fn from_parts(generations: [I::Generation; N], items: UnrestrictedSlots<IT, N, I>) -> Self;
+
+    fn as_parts(&self) -> (&[I::Generation; N], &UnrestrictedSlots<IT, N, I>);
+}
+
+impl<IT, const N: usize, I: SlotIndex> WithGenerations<IT, N, I> for GenerationalSlots<IT, N, I> {
+    const NAME: &'static str = "GenerationalSlots";
+
+    fn from_parts(generations: [I::Generation; N], items: UnrestrictedSlots<IT, N, I>) -> Self {
+        GenerationalSlots::from_parts(generations, items)
+    }
+
+    fn as_parts(&self) -> (&[I::Generation; N], &UnrestrictedSlots<IT, N, I>) {
+        GenerationalSlots::as_parts(self)
+    }
+}
+
+impl<IT, const N: usize, I: SlotIndex, O: OwnerIdSource> WithGenerations<IT, N, I>
+    for Slots<IT, N, I, O>
+{
+    const NAME: &'static str = "Slots";
+
+    fn from_parts(generations: [I::Generation; N], items: UnrestrictedSlots<IT, N, I>) -> Self {
+        Slots::from_parts(generations, items)
+    }
+
+    fn as_parts(&self) -> (&[I::Generation; N], &UnrestrictedSlots<IT, N, I>) {
+        Slots::as_parts(self)
+    }
+}
+
+fn serialize_with_generations<
+    T: WithGenerations<IT, N, I>,
+    IT: Serialize,
+    const N: usize,
+    I: SlotIndex,
+    S: Serializer,
+>(
+    slots: &T,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let (generations, items) = slots.as_parts();
+
+    let mut state = serializer.serialize_struct(T::NAME, 2)?;
+    state.serialize_field("generations", &Generations(generations))?;
+    state.serialize_field("items", items)?;
+    state.end()
+}
+
+const GENERATIONAL_FIELDS: &[&str] = &["generations", "items"];
+
+enum GenerationalField {
+    Generations,
+    Items,
+}
+
+impl<'de> Deserialize<'de> for GenerationalField {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct FieldVisitor;
+
+        impl Visitor<'_> for FieldVisitor {
+            type Value = GenerationalField;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("`generations` or `items`")
+            }
+
+            fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+                match value {
+                    0 => Ok(GenerationalField::Generations),
+                    1 => Ok(GenerationalField::Items),
+                    _ => Err(de::Error::invalid_value(
+                        de::Unexpected::Unsigned(value),
+                        &self,
+                    )),
+                }
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                match value {
+                    "generations" => Ok(GenerationalField::Generations),
+                    "items" => Ok(GenerationalField::Items),
+                    _ => Err(de::Error::unknown_field(value, GENERATIONAL_FIELDS)),
+                }
+            }
+        }
+
+        deserializer.deserialize_identifier(FieldVisitor)
+    }
+}
+
+struct GenerationalVisitor<T, IT, const N: usize, I>(PhantomData<(T, IT, I)>);
+
+impl<'de, T: WithGenerations<IT, N, I>, IT: Deserialize<'de>, const N: usize, I: SlotIndex>
+    Visitor<'de> for GenerationalVisitor<T, IT, N, I>
+{
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "struct {}", T::NAME)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let GenerationsArray(generations) = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let items = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+
+        Ok(T::from_parts(generations, items))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut generations = None;
+        let mut items = None;
+
+        while let Some(field) = map.next_key()? {
+            match field {
+                GenerationalField::Generations => {
+                    if generations.is_some() {
+                        return Err(de::Error::duplicate_field("generations"));
+                    }
+                    let GenerationsArray(value) = map.next_value()?;
+                    generations = Some(value);
+                }
+                GenerationalField::Items => {
+                    if items.is_some() {
+                        return Err(de::Error::duplicate_field("items"));
+                    }
+                    items = Some(map.next_value()?);
+                }
+            }
+        }
+
+        let generations = generations.ok_or_else(|| de::Error::missing_field("generations"))?;
+        let items = items.ok_or_else(|| de::Error::missing_field("items"))?;
+
+        Ok(T::from_parts(generations, items))
+    }
+}
+
+fn deserialize_with_generations<
+    'de,
+    T: WithGenerations<IT, N, I>,
+    IT: Deserialize<'de>,
+    const N: usize,
+    I: SlotIndex,
+    D: Deserializer<'de>,
+>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    deserializer.deserialize_struct(
+        T::NAME,
+        GENERATIONAL_FIELDS,
+        GenerationalVisitor(PhantomData),
+    )
+}
+
+impl<IT: Serialize, const N: usize, I: SlotIndex> Serialize for GenerationalSlots<IT, N, I> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_with_generations(self, serializer)
+    }
+}
+
+impl<'de, IT: Deserialize<'de>, const N: usize, I: SlotIndex> Deserialize<'de>
+    for GenerationalSlots<IT, N, I>
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_with_generations(deserializer)
+    }
+}
+
+impl<IT: Serialize, const N: usize, I: SlotIndex, O: OwnerIdSource> Serialize
+    for Slots<IT, N, I, O>
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_with_generations(self, serializer)
+    }
+}
+
+impl<'de, IT: Deserialize<'de>, const N: usize, I: SlotIndex, O: OwnerIdSource> Deserialize<'de>
+    for Slots<IT, N, I, O>
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_with_generations(deserializer)
+    }
+}
@@ -0,0 +1,183 @@
+//! Slots object that can be shared between interrupt handlers and the main program.
+//!
+//! [`SharedSlots`] wraps a [`Slots`] object and protects every access using a critical
+//! section provided by the [`critical-section`](https://docs.rs/critical-section) crate.
+//! This makes it possible to store and take elements through a shared reference, for
+//! example from an interrupt handler, on targets that do not have atomic instructions.
+//!
+//! This module is only available when the `critical-section` feature is enabled. The
+//! application must provide a critical section implementation, as described in the
+//! documentation of the `critical-section` crate.
+//!
+//! ```rust
+//! use slots::shared::SharedSlots;
+//!
+//! static PACKETS: SharedSlots<[u8; 4], 8> = SharedSlots::new();
+//!
+//! // in an interrupt handler
+//! let key = PACKETS.store([1, 2, 3, 4]).unwrap();
+//!
+//! // in the main loop
+//! assert_eq!(10, PACKETS.read(&key, |packet| packet.iter().sum::<u8>()));
+//! assert_eq!([1, 2, 3, 4], PACKETS.take(key));
+//! ```
+//!
+//! **Note:** the callbacks passed to [`read`](SharedSlots::read) and [`modify`](SharedSlots::modify)
+//! are executed inside the critical section, so they should be kept short.
+//!
+//! [`Slots`]: crate::slots::Slots
+
+use core::cell::RefCell;
+
+use critical_section::Mutex;
+
+use crate::index::SlotIndex;
+use crate::owner::{DefaultOwnerId, OwnerIdSource};
+use crate::slots::{Key, Slots};
+
+/// Slots object that can be shared between interrupt handlers and the main program.
+///
+/// The struct has four type parameters:
+///  - `IT` is the type of the stored data
+///  - `N` is the number of slots.
+///  - `I` is the type used to store slot indices, see [`SlotIndex`]. Defaults to `usize`.
+///  - `O` is the strategy used to allocate owner ids, see [`OwnerIdSource`]. Defaults to [`DefaultOwnerId`].
+///
+/// For more information, see the [module level documentation](crate::shared)
+pub struct SharedSlots<IT, const N: usize, I: SlotIndex = usize, O: OwnerIdSource = DefaultOwnerId>
+{
+    inner: Mutex<RefCell<Slots<IT, N, I, O>>>,
+}
+
+impl<IT, const N: usize, I: SlotIndex, O: OwnerIdSource> Default for SharedSlots<IT, N, I, O> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<IT, const N: usize, I: SlotIndex, O: OwnerIdSource> SharedSlots<IT, N, I, O> {
+    /// Creates a new, empty SharedSlots object.
+    ///
+    /// This function can be used in const context, for example to initialize a `static`.
+    pub const fn new() -> Self {
+        Self {
+            inner: Mutex::new(RefCell::new(Slots::new())),
+        }
+    }
+
+    /// Executes the `function` callback with exclusive access to the wrapped [`Slots`] object.
+    ///
+    /// The callback is executed inside a critical section. Accessing the same `SharedSlots`
+    /// object from inside the callback panics.
+    ///
+    /// ```
+    /// # use slots::shared::SharedSlots;
+    /// # let slots: SharedSlots<_, 4> = SharedSlots::new();
+    /// slots.store(2).unwrap();
+    /// slots.store(4).unwrap();
+    ///
+    /// assert_eq!(6, slots.with(|slots| slots.iter().sum::<i32>()));
+    /// ```
+    pub fn with<T>(&self, function: impl FnOnce(&mut Slots<IT, N, I, O>) -> T) -> T {
+        critical_section::with(|cs| function(&mut self.inner.borrow_ref_mut(cs)))
+    }
+
+    /// Returns the number of slots
+    ///
+    /// ```
+    /// # use slots::shared::SharedSlots;
+    /// let slots: SharedSlots<f32, 4> = SharedSlots::new();
+    ///
+    /// assert_eq!(4, slots.capacity());
+    /// ```
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Returns the number of occupied slots
+    ///
+    /// ```
+    /// # use slots::shared::SharedSlots;
+    /// let slots: SharedSlots<_, 4> = SharedSlots::new();
+    ///
+    /// assert_eq!(0, slots.count());
+    ///
+    /// slots.store(3).unwrap();
+    /// slots.store(6).unwrap();
+    ///
+    /// assert_eq!(2, slots.count());
+    /// ```
+    pub fn count(&self) -> usize {
+        self.with(|slots| slots.count())
+    }
+
+    /// Returns whether all the slots are occupied and the next [`store()`](SharedSlots::store) will fail.
+    pub fn is_full(&self) -> bool {
+        self.with(|slots| slots.is_full())
+    }
+
+    /// Store an element in a free slot and return the key to access it.
+    ///
+    /// Storing a variable takes ownership over it. If the storage is full,
+    /// the inserted data is returned in the return value.
+    pub fn store(&self, item: IT) -> Result<Key<IT, N, I, O>, IT> {
+        self.with(|slots| slots.store(item))
+    }
+
+    /// Remove and return the element that belongs to the key.
+    pub fn take(&self, key: Key<IT, N, I, O>) -> IT {
+        self.with(|slots| slots.take(key))
+    }
+
+    /// Read the element that belongs to the key.
+    ///
+    /// This operation does not move ownership so the `function` callback must be used
+    /// to access the stored element. The callback may return arbitrary derivative of the element.
+    ///
+    /// ```
+    /// # use slots::shared::SharedSlots;
+    /// # let slots: SharedSlots<_, 4> = SharedSlots::new();
+    ///
+    /// let k = slots.store(3).unwrap();
+    ///
+    /// assert_eq!(4, slots.read(&k, |elem| {
+    ///     elem + 1
+    /// }));
+    /// ```
+    pub fn read<T>(&self, key: &Key<IT, N, I, O>, function: impl FnOnce(&IT) -> T) -> T {
+        self.with(|slots| slots.read(key, function))
+    }
+
+    /// Read the element that belongs to a particular index. Since the index may point to
+    /// a free slot or outside the collection, this operation may return None without invoking the callback.
+    ///
+    /// This operation does not move ownership so the `function` callback must be used
+    /// to access the stored element. The callback may return arbitrary derivative of the element.
+    pub fn try_read<T>(&self, index: usize, function: impl FnOnce(&IT) -> T) -> Option<T> {
+        self.with(|slots| slots.try_read(index, function))
+    }
+
+    /// Access the element that belongs to the key for modification.
+    ///
+    /// This operation does not move ownership so the `function` callback must be used
+    /// to access the stored element. The callback may return arbitrary derivative of the element.
+    ///
+    /// ```
+    /// # use slots::shared::SharedSlots;
+    /// # let slots: SharedSlots<_, 4> = SharedSlots::new();
+    ///
+    /// let k = slots.store(3).unwrap();
+    ///
+    /// assert_eq!("found", slots.modify(&k, |elem| {
+    ///     *elem = *elem + 1;
+    ///
+    ///     "found"
+    /// }));
+    ///
+    /// // Assert that the stored data was modified
+    /// assert_eq!(4, slots.take(k));
+    /// ```
+    pub fn modify<T>(&self, key: &Key<IT, N, I, O>, function: impl FnOnce(&mut IT) -> T) -> T {
+        self.with(|slots| slots.modify(key, function))
+    }
+}
@@ -93,6 +93,23 @@
 //! assert_eq!(6, slots.read(&k2, |&e| e));
 //! ```
 //!
+//! The key can also be used to borrow the stored data directly, using the [`get`] and [`get_mut`]
+//! methods, or by indexing the collection.
+//!
+//! ```rust
+//! # use slots::slots::Slots;
+//! #
+//! # let mut slots: Slots<_, 2> = Slots::new();
+//! #
+//! let k = slots.store(4).unwrap();
+//!
+//! slots[&k] += 1;
+//! *slots.get_mut(&k) *= 2;
+//!
+//! assert_eq!(&10, slots.get(&k));
+//! assert_eq!(10, slots[&k]);
+//! ```
+//!
 //! # Read using a numerical index
 //!
 //! It's possible to extract the index of the allocated slot from the [`Key`] object, using the [`index`] method.
@@ -114,71 +131,151 @@
 //! [`take`]: crate::slots::Slots::take
 //! [`read`]: crate::slots::Slots::read
 //! [`modify`]: crate::slots::Slots::modify
+//! [`get`]: crate::slots::Slots::get
+//! [`get_mut`]: crate::slots::Slots::get_mut
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+use core::iter::FusedIterator;
 use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+use core::ops::{Index, IndexMut};
+use core::ptr::addr_of_mut;
 
-use crate::iterator::Iter;
-use crate::unrestricted::UnrestrictedSlots;
+use crate::error::{SlotsError, TakeError};
+use crate::index::SlotIndex;
+use crate::iterator::{Iter, IterIndexed};
+use crate::keyed::{Keyed, RawKey};
+use crate::owner::{DefaultOwnerId, OwnerIdSource};
+use crate::unrestricted::{self, UnrestrictedSlots};
 
 /// The key used to access stored elements.
 ///
 /// **Important:** It should only be used to access the same collection that returned it.
-/// When the `runtime_checks` feature is disabled, extra care must be taken to ensure this constraint.
+/// When the owner id strategy does not allocate ids (e.g. because the `runtime_checks` feature
+/// is disabled), extra care must be taken to ensure this constraint. See [`crate::owner`].
+///
+/// Every slot has a generation of type [`I::Generation`](SlotIndex::Generation), which is
+/// incremented when the slot is released.
+/// Keys record the generation of their slot, so keys that were returned before the collection
+/// was [cleared](Slots::clear) or [drained](Slots::drain) are rejected, regardless of the owner
+/// id strategy. Generations wrap around, so a stale key is only accepted again after its slot was
+/// reused as many times as the generation type has values.
+///
+/// The index is stored as a non-zero value, so `Option<Key>` is the same size as `Key`.
 #[derive(Debug)]
-pub struct Key<IT, const N: usize> {
-    #[cfg(feature = "runtime_checks")]
-    owner_id: usize,
-    index: usize,
+pub struct Key<IT, const N: usize, I: SlotIndex = usize, O: OwnerIdSource = DefaultOwnerId> {
+    raw: RawKey<I, O, I::Generation>,
     _item_marker: PhantomData<IT>,
 }
 
-impl<IT, const N: usize> Key<IT, N> {
-    fn new(owner: &Slots<IT, N>, idx: usize) -> Self {
+impl<IT, const N: usize, I: SlotIndex, O: OwnerIdSource> Key<IT, N, I, O> {
+    pub(crate) fn new(raw: RawKey<I, O, I::Generation>) -> Self {
         Self {
-            #[cfg(feature = "runtime_checks")]
-            owner_id: owner.id,
-            index: idx,
+            raw,
             _item_marker: PhantomData,
         }
     }
 
+    #[cfg(target_has_atomic = "ptr")]
+    pub(crate) fn raw(&self) -> &RawKey<I, O, I::Generation> {
+        &self.raw
+    }
+
     pub fn index(&self) -> usize {
-        self.index
+        self.raw.index()
     }
 }
 
 /// Slots object that provides strict access control for the stored data.
 ///
-/// The struct has two type parameters:
+/// The struct has four type parameters:
 ///  - `IT` is the type of the stored data
 ///  - `N` is the number of slots.
+///  - `I` is the type used to store slot indices, see [`SlotIndex`]. Defaults to `usize`.
+///  - `O` is the strategy used to allocate owner ids, see [`OwnerIdSource`]. Defaults to [`DefaultOwnerId`].
 ///
 /// For more information, see the [module level documentation](./index.html)
-#[derive(Default)]
-pub struct Slots<IT, const N: usize> {
-    #[cfg(feature = "runtime_checks")]
-    id: usize,
-    inner: UnrestrictedSlots<IT, N>,
+pub struct Slots<IT, const N: usize, I: SlotIndex = usize, O: OwnerIdSource = DefaultOwnerId> {
+    keyed: Keyed<UnrestrictedSlots<IT, N, I>, [I::Generation; N], O>,
 }
 
-#[cfg(feature = "runtime_checks")]
-fn new_instance_id() -> usize {
-    use core::sync::atomic::{AtomicUsize, Ordering};
-
-    static COUNTER: AtomicUsize = AtomicUsize::new(0);
-
-    COUNTER.fetch_add(1, Ordering::Relaxed)
+impl<IT, const N: usize, I: SlotIndex, O: OwnerIdSource> Default for Slots<IT, N, I, O> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
-impl<IT, const N: usize> Slots<IT, N> {
+impl<IT, const N: usize, I: SlotIndex, O: OwnerIdSource> Slots<IT, N, I, O> {
     /// Creates a new, empty Slots object.
-    pub fn new() -> Self {
+    ///
+    /// This function can be used in const context, for example to initialize a `static`:
+    ///
+    /// ```
+    /// # use slots::slots::Slots;
+    /// use std::sync::Mutex;
+    ///
+    /// static POOL: Mutex<Slots<u32, 32>> = Mutex::new(Slots::new());
+    ///
+    /// let key = POOL.lock().unwrap().store(5).unwrap();
+    /// assert_eq!(5, POOL.lock().unwrap().take(key));
+    /// ```
+    ///
+    /// The instance receives its owner id when the first element is stored.
+    pub const fn new() -> Self {
         Self {
-            #[cfg(feature = "runtime_checks")]
-            id: new_instance_id(),
-            inner: UnrestrictedSlots::new(),
+            keyed: Keyed::new(UnrestrictedSlots::new(), [I::Generation::ZERO; N]),
+        }
+    }
+
+    /// Initializes an empty Slots object in the memory pointed to by `slot`, and returns a
+    /// reference to it.
+    ///
+    /// Unlike [`new`](Slots::new), this method does not create the object on the stack first,
+    /// so it can be used to initialize collections that are too large for the stack.
+    ///
+    /// ```
+    /// # use core::mem::MaybeUninit;
+    /// # use slots::slots::Slots;
+    /// let mut memory = MaybeUninit::uninit();
+    /// let slots: &mut Slots<u32, 1024> = Slots::init_in_place(&mut memory);
+    ///
+    /// let k = slots.store(3).unwrap();
+    /// assert_eq!(3, slots.take(k));
+    /// # unsafe { memory.assume_init_drop() };
+    /// ```
+    ///
+    /// **Note:** like with any `MaybeUninit`, the object is not dropped automatically.
+    pub fn init_in_place(slot: &mut MaybeUninit<Self>) -> &mut Self {
+        let this = slot.as_mut_ptr();
+
+        // Safety: the pointer is derived from a mutable reference
+        unsafe {
+            Keyed::init_in_place(addr_of_mut!((*this).keyed), |inner| {
+                UnrestrictedSlots::init_raw(inner)
+            });
+
+            slot.assume_init_mut()
         }
     }
 
+    /// Creates a new, empty Slots object directly on the heap.
+    ///
+    /// ```
+    /// # use slots::slots::Slots;
+    /// let mut slots: Box<Slots<u32, 100_000>> = Slots::new_boxed();
+    ///
+    /// let k = slots.store(3).unwrap();
+    /// assert_eq!(3, slots.take(k));
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn new_boxed() -> Box<Self> {
+        let mut boxed = Box::new_uninit();
+        Self::init_in_place(&mut boxed);
+
+        // Safety: the object was initialized above
+        unsafe { boxed.assume_init() }
+    }
+
     /// Returns a read-only iterator.
     /// The iterator can be used to read data from all occupied slots.
     ///
@@ -193,17 +290,105 @@ impl<IT, const N: usize> Slots<IT, N> {
     ///
     /// assert_eq!(true, slots.iter().any(|&x| x < 3));
     /// ```
-    pub fn iter(&self) -> Iter<IT> {
-        self.inner.iter()
+    pub fn iter(&self) -> Iter<'_, IT> {
+        self.keyed.inner.iter()
+    }
+
+    /// Returns a read-only iterator that yields the index of every occupied slot together with
+    /// the stored element.
+    ///
+    /// The indices don't grant access to the elements like keys do, but they can be used with
+    /// the index based methods, like [`try_read`](Slots::try_read) and [`try_get`](Slots::try_get).
+    ///
+    /// **Note:** Do not rely on the order in which the elements are returned.
+    ///
+    /// ```
+    /// # use slots::slots::Slots;
+    /// # let mut slots: Slots<_, 4> = Slots::new();
+    /// let k = slots.store(2).unwrap();
+    /// slots.store(4).unwrap();
+    ///
+    /// let (index, _) = slots.iter_indexed().find(|&(_, &x)| x == 2).unwrap();
+    ///
+    /// assert_eq!(k.index(), index);
+    /// assert_eq!(Some(3), slots.try_read(index, |&x| x + 1));
+    /// ```
+    pub fn iter_indexed(&self) -> IterIndexed<'_, IT> {
+        self.keyed.inner.iter_indexed()
+    }
+
+    /// Removes every element and returns them in an iterator.
+    ///
+    /// The collection is empty after the iterator is dropped, even if it was not fully consumed.
+    /// Keys returned before the call are rejected, like after [`clear`](Slots::clear).
+    ///
+    /// **Note:** Do not rely on the order in which the elements are returned.
+    ///
+    /// ```
+    /// # use slots::slots::Slots;
+    /// # let mut slots: Slots<_, 4> = Slots::new();
+    /// slots.store(2).unwrap();
+    /// slots.store(4).unwrap();
+    ///
+    /// assert_eq!(6, slots.drain().sum::<i32>());
+    /// assert_eq!(0, slots.count());
+    /// ```
+    pub fn drain(&mut self) -> Drain<'_, IT, N, I> {
+        self.keyed.invalidate_keys();
+
+        Drain {
+            inner: self.keyed.inner.drain(),
+        }
     }
 
-    #[cfg(feature = "runtime_checks")]
-    fn verify_key(&self, key: &Key<IT, N>) {
-        assert_eq!(key.owner_id, self.id, "Key used in wrong instance");
+    /// Drops every element, and releases all slots.
+    ///
+    /// Keys returned before the collection was cleared are rejected, the same way as keys of a
+    /// different instance are, even if the owner id strategy does not allocate ids.
+    ///
+    /// If the destructor of an element panics, the remaining elements are leaked, and the
+    /// collection is left empty.
+    ///
+    /// ```should_panic
+    /// # use slots::slots::Slots;
+    /// # let mut slots: Slots<_, 4> = Slots::new();
+    /// let key = slots.store(2).unwrap();
+    ///
+    /// slots.clear();
+    /// assert_eq!(0, slots.count());
+    ///
+    /// slots.store(4).unwrap();
+    /// slots.take(key); // this panics
+    /// ```
+    pub fn clear(&mut self) {
+        // invalidate first, so keys are rejected even if a destructor panics
+        self.keyed.invalidate_keys();
+        self.keyed.inner.clear();
     }
 
-    #[cfg(not(feature = "runtime_checks"))]
-    fn verify_key(&self, _key: &Key<IT, N>) {}
+    /// Wraps a restored collection. The generation of every occupied slot is incremented, so keys
+    /// from before the collection was serialized are rejected, even if the owner id strategy does
+    /// not allocate ids.
+    #[cfg(feature = "serde")]
+    pub(crate) fn from_parts(
+        generations: [I::Generation; N],
+        inner: UnrestrictedSlots<IT, N, I>,
+    ) -> Self {
+        let mut keyed = Keyed::new(inner, generations);
+        keyed.invalidate_keys();
+
+        Self { keyed }
+    }
+
+    #[cfg(feature = "serde")]
+    pub(crate) fn as_parts(&self) -> (&[I::Generation; N], &UnrestrictedSlots<IT, N, I>) {
+        (self.keyed.generations(), &self.keyed.inner)
+    }
+
+    /// Returns the generation of a slot, or `None` if the slot is outside the collection.
+    pub(crate) fn generation(&self, idx: usize) -> Option<I::Generation> {
+        self.keyed.generation(idx)
+    }
 
     /// Returns the number of slots
     ///
@@ -231,7 +416,7 @@ impl<IT, const N: usize> Slots<IT, N> {
     /// assert_eq!(2, slots.count());
     /// ```
     pub fn count(&self) -> usize {
-        self.inner.count()
+        self.keyed.inner.count()
     }
 
     /// Returns whether all the slots are occupied and the next [`store()`](#method.store) will fail.
@@ -251,22 +436,159 @@ impl<IT, const N: usize> Slots<IT, N> {
     /// assert_eq!(true, slots.is_full());
     /// ```
     pub fn is_full(&self) -> bool {
-        self.inner.is_full()
+        self.keyed.inner.is_full()
     }
 
     /// Store an element in a free slot and return the key to access it.
     ///
     /// Storing a variable takes ownership over it. If the storage is full,
     /// the inserted data is returned in the return value.
-    pub fn store(&mut self, item: IT) -> Result<Key<IT, N>, IT> {
-        self.inner.store(item).map(|idx| Key::new(self, idx))
+    pub fn store(&mut self, item: IT) -> Result<Key<IT, N, I, O>, IT> {
+        self.keyed.store(item).map(Key::new)
+    }
+
+    /// Store an element in a free slot and return the key to access it.
+    ///
+    /// If the storage is full, the inserted data is returned in [`SlotsError::Full`].
+    ///
+    /// ```
+    /// # use slots::error::SlotsError;
+    /// # use slots::slots::Slots;
+    /// let mut slots: Slots<_, 1> = Slots::new();
+    ///
+    /// assert!(slots.try_store(3).is_ok());
+    /// assert_eq!(Some(4), slots.try_store(4).err().and_then(SlotsError::into_item));
+    /// ```
+    pub fn try_store(&mut self, item: IT) -> Result<Key<IT, N, I, O>, SlotsError<IT>> {
+        self.store(item).map_err(SlotsError::Full)
+    }
+
+    /// Store an element created by `function` in a free slot, and return the key to access it.
+    ///
+    /// `function` receives a reference to the key that will be returned, so the element can
+    /// record its own index, for example to use it with [`try_read`](Slots::try_read). If
+    /// `function` panics, the collection is left unchanged.
+    ///
+    /// If the storage is full, `function` is not called and `None` is returned. Unlike
+    /// [`store`](Slots::store), there is no element to hand back in that case, so the result is
+    /// an `Option` instead of a `Result`.
+    ///
+    /// ```
+    /// # use slots::slots::Slots;
+    /// # let mut slots: Slots<_, 4> = Slots::new();
+    /// struct Timer {
+    ///     this: usize,
+    ///     period: u32,
+    /// }
+    ///
+    /// let key = slots
+    ///     .store_with(|key| Timer { this: key.index(), period: 10 })
+    ///     .unwrap();
+    ///
+    /// assert_eq!(key.index(), slots.get(&key).this);
+    /// ```
+    pub fn store_with(
+        &mut self,
+        function: impl FnOnce(&Key<IT, N, I, O>) -> IT,
+    ) -> Option<Key<IT, N, I, O>> {
+        let key = Key::new(self.keyed.next_key()?);
+        let item = function(&key);
+
+        match self.store(item) {
+            Ok(stored) => {
+                debug_assert_eq!(
+                    key.index(),
+                    stored.index(),
+                    "Element stored at a different index"
+                );
+                Some(key)
+            }
+            Err(_) => unreachable!("The slot of the key is free"),
+        }
+    }
+
+    /// Returns an entry for the slot that the next element will be stored in, or `None` if the
+    /// collection is full.
+    ///
+    /// The index is known before the element is created, so the element can refer to itself,
+    /// for example with [`try_read`](Slots::try_read). Dropping the entry without inserting
+    /// leaves the collection unchanged.
+    ///
+    /// ```
+    /// # use slots::slots::Slots;
+    /// # let mut slots: Slots<_, 4> = Slots::new();
+    /// struct Node {
+    ///     this: usize,
+    /// }
+    ///
+    /// let entry = slots.vacant_entry().unwrap();
+    /// let this = entry.index();
+    /// let key = entry.insert(Node { this });
+    ///
+    /// assert_eq!(key.index(), slots.get(&key).this);
+    /// ```
+    pub fn vacant_entry(&mut self) -> Option<VacantEntry<'_, IT, N, I, O>> {
+        let inner = self.keyed.inner.vacant_entry()?;
+        let index = inner.index();
+
+        Some(VacantEntry { slots: self, index })
+    }
+
+    /// Returns an entry for the element that belongs to the key.
+    ///
+    /// The key is verified once, when the entry is created, and can be recovered using
+    /// [`OccupiedEntry::into_key`] if the element is not removed.
+    ///
+    /// ```
+    /// # use slots::slots::Slots;
+    /// # let mut slots: Slots<_, 4> = Slots::new();
+    /// let k = slots.store(3).unwrap();
+    ///
+    /// let mut entry = slots.occupied_entry(k);
+    /// *entry.get_mut() += 1;
+    /// assert_eq!(4, entry.insert(5));
+    /// assert_eq!(5, entry.remove());
+    /// ```
+    pub fn occupied_entry(&mut self, key: Key<IT, N, I, O>) -> OccupiedEntry<'_, IT, N, I, O> {
+        self.get(&key);
+
+        OccupiedEntry { slots: self, key }
     }
 
     /// Remove and return the element that belongs to the key.
-    pub fn take(&mut self, key: Key<IT, N>) -> IT {
-        self.verify_key(&key);
+    pub fn take(&mut self, key: Key<IT, N, I, O>) -> IT {
+        self.keyed.take(key.raw)
+    }
 
-        self.inner.take(key.index).expect("Invalid key")
+    /// Remove and return the element that belongs to the key.
+    ///
+    /// Unlike [`take`](Slots::take), this method returns an error instead of panicking if the
+    /// key belongs to a different instance. If the element can't be taken,
+    /// the key is handed back in the [`TakeError`].
+    ///
+    /// ```
+    /// # use slots::error::SlotsError;
+    /// # use slots::slots::Slots;
+    /// let mut a: Slots<_, 4> = Slots::new();
+    /// let mut b: Slots<i32, 4> = Slots::new();
+    ///
+    /// let k1 = a.store(3).unwrap();
+    /// let k2 = a.store(4).unwrap();
+    /// # #[cfg(feature = "runtime_checks")]
+    /// # {
+    /// let error = b.try_take(k1).unwrap_err();
+    /// assert_eq!(SlotsError::WrongOwner, error.error);
+    /// assert_eq!(3, a.take(error.key));
+    /// # }
+    /// assert_eq!(Ok(4), a.try_take(k2).map_err(|error| error.error));
+    /// ```
+    pub fn try_take(
+        &mut self,
+        key: Key<IT, N, I, O>,
+    ) -> Result<IT, TakeError<Key<IT, N, I, O>, IT>> {
+        self.keyed
+            .try_take(key.raw)
+            .map_err(|error| error.map_key(Key::new))
     }
 
     /// Read the element that belongs to the key.
@@ -284,10 +606,8 @@ impl<IT, const N: usize> Slots<IT, N> {
     ///     elem + 1
     /// }));
     /// ```
-    pub fn read<T>(&self, key: &Key<IT, N>, function: impl FnOnce(&IT) -> T) -> T {
-        self.verify_key(key);
-
-        self.inner.read(key.index, function).expect("Invalid key")
+    pub fn read<T>(&self, key: &Key<IT, N, I, O>, function: impl FnOnce(&IT) -> T) -> T {
+        function(self.get(key))
     }
 
     /// Read the element that belongs to a particular index. Since the index may point to
@@ -316,7 +636,35 @@ impl<IT, const N: usize> Slots<IT, N> {
     /// }));
     /// ```
     pub fn try_read<T>(&self, index: usize, function: impl FnOnce(&IT) -> T) -> Option<T> {
-        self.inner.read(index, function)
+        self.keyed.inner.read(index, function)
+    }
+
+    /// Read the element that belongs to the key.
+    ///
+    /// This operation does not move ownership so the `function` callback must be used
+    /// to access the stored element. The callback may return arbitrary derivative of the element.
+    ///
+    /// Unlike [`read`](Slots::read), this method returns an error instead of panicking if the
+    /// key belongs to a different instance.
+    ///
+    /// ```
+    /// # use slots::error::SlotsError;
+    /// # use slots::slots::Slots;
+    /// let mut a: Slots<_, 4> = Slots::new();
+    /// let b: Slots<i32, 4> = Slots::new();
+    ///
+    /// let k = a.store(3).unwrap();
+    ///
+    /// assert_eq!(Ok(4), a.try_read_key(&k, |elem| elem + 1));
+    /// # #[cfg(feature = "runtime_checks")]
+    /// assert_eq!(Err(SlotsError::WrongOwner), b.try_read_key(&k, |elem| elem + 1));
+    /// ```
+    pub fn try_read_key<T>(
+        &self,
+        key: &Key<IT, N, I, O>,
+        function: impl FnOnce(&IT) -> T,
+    ) -> Result<T, SlotsError<IT>> {
+        self.keyed.try_get_key(&key.raw).map(function)
     }
 
     /// Access the element that belongs to the key for modification.
@@ -339,9 +687,295 @@ impl<IT, const N: usize> Slots<IT, N> {
     /// // Assert that the stored data was modified
     /// assert_eq!(4, slots.take(k));
     /// ```
-    pub fn modify<T>(&mut self, key: &Key<IT, N>, function: impl FnOnce(&mut IT) -> T) -> T {
-        self.verify_key(key);
+    pub fn modify<T>(&mut self, key: &Key<IT, N, I, O>, function: impl FnOnce(&mut IT) -> T) -> T {
+        function(self.get_mut(key))
+    }
+
+    /// Access the element that belongs to the key for modification.
+    ///
+    /// This operation does not move ownership so the `function` callback must be used
+    /// to access the stored element. The callback may return arbitrary derivative of the element.
+    ///
+    /// Unlike [`modify`](Slots::modify), this method returns an error instead of panicking if the
+    /// key belongs to a different instance.
+    ///
+    /// ```
+    /// # use slots::error::SlotsError;
+    /// # use slots::slots::Slots;
+    /// let mut a: Slots<_, 4> = Slots::new();
+    /// let mut b: Slots<i32, 4> = Slots::new();
+    ///
+    /// let k = a.store(3).unwrap();
+    ///
+    /// assert_eq!(Ok(()), a.try_modify(&k, |elem| *elem += 1));
+    /// # #[cfg(feature = "runtime_checks")]
+    /// assert_eq!(Err(SlotsError::WrongOwner), b.try_modify(&k, |elem| *elem += 1));
+    /// assert_eq!(4, a.take(k));
+    /// ```
+    pub fn try_modify<T>(
+        &mut self,
+        key: &Key<IT, N, I, O>,
+        function: impl FnOnce(&mut IT) -> T,
+    ) -> Result<T, SlotsError<IT>> {
+        self.keyed.try_get_key_mut(&key.raw).map(function)
+    }
+
+    /// Returns a reference to the element that belongs to the key.
+    ///
+    /// ```
+    /// # use slots::slots::Slots;
+    /// # let mut slots: Slots<_, 4> = Slots::new();
+    ///
+    /// let k = slots.store(3).unwrap();
+    ///
+    /// assert_eq!(&3, slots.get(&k));
+    /// ```
+    pub fn get(&self, key: &Key<IT, N, I, O>) -> &IT {
+        self.keyed.get(&key.raw)
+    }
+
+    /// Returns a mutable reference to the element that belongs to the key.
+    ///
+    /// ```
+    /// # use slots::slots::Slots;
+    /// # let mut slots: Slots<_, 4> = Slots::new();
+    ///
+    /// let k = slots.store(3).unwrap();
+    ///
+    /// *slots.get_mut(&k) += 1;
+    ///
+    /// assert_eq!(4, slots.take(k));
+    /// ```
+    pub fn get_mut(&mut self, key: &Key<IT, N, I, O>) -> &mut IT {
+        self.keyed.get_mut(&key.raw)
+    }
+
+    /// Returns mutable references to the elements that belong to multiple keys at once.
+    ///
+    /// Since keys can't be copied, every key refers to a different slot.
+    ///
+    /// ```
+    /// # use slots::slots::Slots;
+    /// # let mut slots: Slots<_, 4> = Slots::new();
+    ///
+    /// let k1 = slots.store(3).unwrap();
+    /// let k2 = slots.store(5).unwrap();
+    ///
+    /// let [a, b] = slots.get_many_mut([&k1, &k2]);
+    /// *a -= 1;
+    /// *b += 1;
+    ///
+    /// assert_eq!(2, slots.take(k1));
+    /// assert_eq!(6, slots.take(k2));
+    /// ```
+    pub fn get_many_mut<const M: usize>(&mut self, keys: [&Key<IT, N, I, O>; M]) -> [&mut IT; M] {
+        self.keyed.get_many_mut(keys.map(|key| &key.raw))
+    }
+
+    /// Returns a reference to the element that belongs to a particular index.
+    ///
+    /// This operation is fallible. If `index` addresses a free slot or is outside the collection,
+    /// `None` is returned.
+    ///
+    /// ```
+    /// # use slots::slots::Slots;
+    /// # let mut slots: Slots<_, 4> = Slots::new();
+    ///
+    /// let k = slots.store(3).unwrap();
+    /// let idx = k.index();
+    ///
+    /// assert_eq!(Some(&3), slots.try_get(idx));
+    ///
+    /// slots.take(k);
+    ///
+    /// assert_eq!(None, slots.try_get(idx));
+    /// ```
+    pub fn try_get(&self, index: usize) -> Option<&IT> {
+        self.keyed.inner.get(index)
+    }
+}
+
+impl<IT, const N: usize, I: SlotIndex, O: OwnerIdSource> Index<&Key<IT, N, I, O>>
+    for Slots<IT, N, I, O>
+{
+    type Output = IT;
+
+    fn index(&self, key: &Key<IT, N, I, O>) -> &IT {
+        self.get(key)
+    }
+}
+
+impl<IT, const N: usize, I: SlotIndex, O: OwnerIdSource> IndexMut<&Key<IT, N, I, O>>
+    for Slots<IT, N, I, O>
+{
+    fn index_mut(&mut self, key: &Key<IT, N, I, O>) -> &mut IT {
+        self.get_mut(key)
+    }
+}
+
+impl<'a, IT, const N: usize, I: SlotIndex, O: OwnerIdSource> IntoIterator
+    for &'a Slots<IT, N, I, O>
+{
+    type Item = &'a IT;
+    type IntoIter = Iter<'a, IT>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<IT, const N: usize, I: SlotIndex, O: OwnerIdSource> IntoIterator for Slots<IT, N, I, O> {
+    type Item = IT;
+    type IntoIter = IntoIter<IT, N, I>;
+
+    /// Consumes the collection, and returns its elements.
+    ///
+    /// ```
+    /// # use slots::slots::Slots;
+    /// # let mut slots: Slots<_, 4> = Slots::new();
+    /// slots.store(2).unwrap();
+    ///
+    /// assert_eq!(vec![2], slots.into_iter().collect::<Vec<_>>());
+    /// ```
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            inner: self.keyed.inner.into_iter(),
+        }
+    }
+}
+
+/// Iterator that removes every element of a [`Slots`] object.
+///
+/// Created by [`Slots::drain`]. Elements that are not consumed are dropped together with the
+/// iterator.
+pub struct Drain<'a, IT, const N: usize, I: SlotIndex = usize> {
+    inner: unrestricted::Drain<'a, IT, N, I>,
+}
+
+impl<IT, const N: usize, I: SlotIndex> Iterator for Drain<'_, IT, N, I> {
+    type Item = IT;
+
+    fn next(&mut self) -> Option<IT> {
+        self.inner.next().map(|(_, item)| item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<IT, const N: usize, I: SlotIndex> ExactSizeIterator for Drain<'_, IT, N, I> {}
+
+impl<IT, const N: usize, I: SlotIndex> FusedIterator for Drain<'_, IT, N, I> {}
+
+/// Iterator that moves the elements out of a [`Slots`] object.
+///
+/// Created by the [`IntoIterator`] implementation of [`Slots`].
+pub struct IntoIter<IT, const N: usize, I: SlotIndex = usize> {
+    inner: unrestricted::IntoIter<IT, N, I>,
+}
+
+impl<IT, const N: usize, I: SlotIndex> Iterator for IntoIter<IT, N, I> {
+    type Item = IT;
+
+    fn next(&mut self) -> Option<IT> {
+        self.inner.next().map(|(_, item)| item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<IT, const N: usize, I: SlotIndex> ExactSizeIterator for IntoIter<IT, N, I> {}
+
+impl<IT, const N: usize, I: SlotIndex> FusedIterator for IntoIter<IT, N, I> {}
+
+/// A free slot of a [`Slots`] object.
+///
+/// Created by [`Slots::vacant_entry`].
+pub struct VacantEntry<
+    'a,
+    IT,
+    const N: usize,
+    I: SlotIndex = usize,
+    O: OwnerIdSource = DefaultOwnerId,
+> {
+    slots: &'a mut Slots<IT, N, I, O>,
+    index: usize,
+}
+
+impl<IT, const N: usize, I: SlotIndex, O: OwnerIdSource> VacantEntry<'_, IT, N, I, O> {
+    /// Returns the index that the element will be stored at. This is the value that
+    /// [`Key::index`] of the returned key will return.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Stores an element in the slot, and returns the key to access it.
+    pub fn insert(self, item: IT) -> Key<IT, N, I, O> {
+        match self.slots.store(item) {
+            Ok(key) => {
+                debug_assert_eq!(
+                    key.index(),
+                    self.index,
+                    "Vacant entry stored at a different index"
+                );
+                key
+            }
+            Err(_) => unreachable!("The slot of a vacant entry is free"),
+        }
+    }
+}
+
+/// An occupied slot of a [`Slots`] object.
+///
+/// Created by [`Slots::occupied_entry`].
+pub struct OccupiedEntry<
+    'a,
+    IT,
+    const N: usize,
+    I: SlotIndex = usize,
+    O: OwnerIdSource = DefaultOwnerId,
+> {
+    slots: &'a mut Slots<IT, N, I, O>,
+    key: Key<IT, N, I, O>,
+}
+
+impl<'a, IT, const N: usize, I: SlotIndex, O: OwnerIdSource> OccupiedEntry<'a, IT, N, I, O> {
+    /// Returns the index of the element. This is the value of [`Key::index`] of the key.
+    pub fn index(&self) -> usize {
+        self.key.index()
+    }
+
+    /// Returns a reference to the element.
+    pub fn get(&self) -> &IT {
+        self.slots.get(&self.key)
+    }
+
+    /// Returns a mutable reference to the element.
+    pub fn get_mut(&mut self) -> &mut IT {
+        self.slots.get_mut(&self.key)
+    }
+
+    /// Converts the entry into a mutable reference to the element, that lives as long as the
+    /// borrow of the collection. The key is consumed.
+    pub fn into_mut(self) -> &'a mut IT {
+        self.slots.get_mut(&self.key)
+    }
+
+    /// Returns the key, and releases the borrow of the collection.
+    pub fn into_key(self) -> Key<IT, N, I, O> {
+        self.key
+    }
+
+    /// Replaces the element, and returns the previous one.
+    pub fn insert(&mut self, item: IT) -> IT {
+        core::mem::replace(self.get_mut(), item)
+    }
 
-        self.inner.modify(key.index, function).expect("Invalid key")
+    /// Removes the element from the collection, and returns it.
+    pub fn remove(self) -> IT {
+        self.slots.take(self.key)
     }
 }
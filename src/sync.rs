@@ -0,0 +1,32 @@
+//! Synchronization primitives used by the concurrent collections.
+//!
+//! When compiled with `--cfg loom`, these are replaced by their [`loom`](https://docs.rs/loom)
+//! counterparts so that the concurrent code paths can be model checked.
+
+#[cfg(loom)]
+pub(crate) use loom::{
+    cell::UnsafeCell,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+#[cfg(not(loom))]
+pub(crate) use core::sync::atomic::{AtomicUsize, Ordering};
+
+/// A `core::cell::UnsafeCell` with the closure based interface of `loom::cell::UnsafeCell`.
+#[cfg(not(loom))]
+pub(crate) struct UnsafeCell<T>(core::cell::UnsafeCell<T>);
+
+#[cfg(not(loom))]
+impl<T> UnsafeCell<T> {
+    pub(crate) const fn new(data: T) -> Self {
+        Self(core::cell::UnsafeCell::new(data))
+    }
+
+    pub(crate) fn with<R>(&self, function: impl FnOnce(*const T) -> R) -> R {
+        function(self.0.get())
+    }
+
+    pub(crate) fn with_mut<R>(&self, function: impl FnOnce(*mut T) -> R) -> R {
+        function(self.0.get())
+    }
+}
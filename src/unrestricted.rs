@@ -40,44 +40,132 @@
 //! [`Slots`]: crate::slots
 //! [aba-problem]: https://en.wikipedia.org/wiki/ABA_problem
 
-use core::mem::replace;
+#[cfg(feature = "alloc")]
+use alloc::boxed::Box;
+use core::iter::FusedIterator;
+use core::mem::MaybeUninit;
+use core::ptr::addr_of_mut;
 
+use crate::error::SlotsError;
+use crate::index::SlotIndex;
 use crate::iterator::*;
-use crate::private::Entry;
+use crate::keyed::RawAccess;
+#[cfg(feature = "serde")]
+use crate::raw::ReleasedSlots;
+use crate::raw::{ArrayStorage, RawSlots};
 
 /// Slots object that provides an unrestricted access control for the stored data.
 ///
-/// The struct has two type parameters:
+/// The struct has three type parameters:
 ///  - `IT` is the type of the stored data
 ///  - `N` is the number of slots.
+///  - `I` is the type used to store slot indices, see [`SlotIndex`]. Defaults to `usize`.
 ///
 /// For more information, see the [module level documentation](crate::unrestricted)
-pub struct UnrestrictedSlots<IT, const N: usize> {
-    items: [Entry<IT>; N],
-    next_free: usize,
-    count: usize,
+pub struct UnrestrictedSlots<IT, const N: usize, I: SlotIndex = usize> {
+    raw: RawSlots<ArrayStorage<IT, N, I>>,
 }
 
-impl<IT, const N: usize> Default for UnrestrictedSlots<IT, N> {
+impl<IT, const N: usize, I: SlotIndex> Default for UnrestrictedSlots<IT, N, I> {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl<IT, const N: usize> UnrestrictedSlots<IT, N> {
+impl<IT, const N: usize, I: SlotIndex> RawAccess for UnrestrictedSlots<IT, N, I> {
+    type Storage = ArrayStorage<IT, N, I>;
+
+    fn raw(&self) -> &RawSlots<Self::Storage> {
+        &self.raw
+    }
+
+    fn raw_mut(&mut self) -> &mut RawSlots<Self::Storage> {
+        &mut self.raw
+    }
+}
+
+impl<IT, const N: usize, I: SlotIndex> UnrestrictedSlots<IT, N, I> {
+    const CAPACITY_FITS_INDEX: () = assert!(
+        N <= I::MAX_CAPACITY,
+        "The capacity can not be represented by the index type"
+    );
+
     /// Creates a new, empty UnrestrictedSlots object.
-    pub fn new() -> Self {
+    ///
+    /// This function can be used in const context, for example to initialize a `static`:
+    ///
+    /// ```
+    /// # use slots::unrestricted::UnrestrictedSlots;
+    /// static SLOTS: UnrestrictedSlots<u32, 16> = UnrestrictedSlots::new();
+    ///
+    /// assert_eq!(0, SLOTS.count());
+    /// ```
+    pub const fn new() -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::CAPACITY_FITS_INDEX;
+
         Self {
-            items: array_init::array_init(|i| {
-                i.checked_sub(1)
-                    .map(Entry::EmptyNext)
-                    .unwrap_or(Entry::EmptyLast)
-            }),
-            next_free: N.saturating_sub(1), // edge case: N == 0
-            count: 0,
+            raw: RawSlots::new(ArrayStorage::new()),
+        }
+    }
+
+    /// Initializes an empty UnrestrictedSlots object in the memory pointed to by `slot`, and
+    /// returns a reference to it.
+    ///
+    /// Unlike [`new`](UnrestrictedSlots::new), this method does not create the object on the
+    /// stack first, so it can be used to initialize collections that are too large for the stack.
+    ///
+    /// ```
+    /// # use core::mem::MaybeUninit;
+    /// # use slots::unrestricted::UnrestrictedSlots;
+    /// let mut memory = MaybeUninit::uninit();
+    /// let slots: &mut UnrestrictedSlots<u32, 1024> = UnrestrictedSlots::init_in_place(&mut memory);
+    ///
+    /// let k = slots.store(3).unwrap();
+    /// assert_eq!(Some(3), slots.take(k));
+    /// # unsafe { memory.assume_init_drop() };
+    /// ```
+    ///
+    /// **Note:** like with any `MaybeUninit`, the object is not dropped automatically.
+    pub fn init_in_place(slot: &mut MaybeUninit<Self>) -> &mut Self {
+        // Safety: the pointer is derived from a mutable reference
+        unsafe {
+            Self::init_raw(slot.as_mut_ptr());
+            slot.assume_init_mut()
         }
     }
 
+    /// Creates a new, empty UnrestrictedSlots object directly on the heap.
+    ///
+    /// ```
+    /// # use slots::unrestricted::UnrestrictedSlots;
+    /// let mut slots: Box<UnrestrictedSlots<u32, 100_000>> = UnrestrictedSlots::new_boxed();
+    ///
+    /// let k = slots.store(3).unwrap();
+    /// assert_eq!(Some(3), slots.take(k));
+    /// ```
+    #[cfg(feature = "alloc")]
+    pub fn new_boxed() -> Box<Self> {
+        let mut boxed = Box::new_uninit();
+        Self::init_in_place(&mut boxed);
+
+        // Safety: the object was initialized above
+        unsafe { boxed.assume_init() }
+    }
+
+    /// Initializes an empty collection in place.
+    ///
+    /// # Safety
+    ///
+    /// `this` must be valid for writes and properly aligned. Any previous value is overwritten
+    /// without being dropped.
+    pub(crate) unsafe fn init_raw(this: *mut Self) {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::CAPACITY_FITS_INDEX;
+
+        RawSlots::init_in_place(addr_of_mut!((*this).raw));
+    }
+
     /// Returns a read-only iterator.
     /// The iterator can be used to read data from all occupied slots.
     ///
@@ -92,8 +180,8 @@ impl<IT, const N: usize> UnrestrictedSlots<IT, N> {
     ///
     /// assert_eq!(true, slots.iter().any(|&x| x < 3));
     /// ```
-    pub fn iter(&self) -> Iter<IT> {
-        Iter::from_entry_slice(self.items.as_slice())
+    pub fn iter(&self) -> Iter<'_, IT> {
+        self.raw.iter()
     }
 
     /// Returns a read-write iterator.
@@ -114,8 +202,141 @@ impl<IT, const N: usize> UnrestrictedSlots<IT, N> {
     ///
     /// assert_eq!(4, slots.take(k).unwrap());
     /// ```
-    pub fn iter_mut(&mut self) -> IterMut<IT> {
-        IterMut::from_entry_slice(self.items.as_mut_slice())
+    pub fn iter_mut(&mut self) -> IterMut<'_, IT> {
+        self.raw.iter_mut()
+    }
+
+    /// Returns a read-only iterator that yields the index of every occupied slot together with
+    /// the stored element.
+    ///
+    /// **Note:** Do not rely on the order in which the elements are returned.
+    ///
+    /// ```
+    /// # use slots::unrestricted::UnrestrictedSlots;
+    /// # let mut slots: UnrestrictedSlots<_, 4> = UnrestrictedSlots::new();
+    /// let k = slots.store(2).unwrap();
+    /// slots.store(4).unwrap();
+    ///
+    /// let (index, _) = slots.iter_indexed().find(|&(_, &x)| x == 2).unwrap();
+    /// assert_eq!(k, index);
+    /// ```
+    pub fn iter_indexed(&self) -> IterIndexed<'_, IT> {
+        self.raw.iter_indexed()
+    }
+
+    /// Returns a read-write iterator that yields the index of every occupied slot together with
+    /// the stored element.
+    ///
+    /// **Note:** Do not rely on the order in which the elements are returned.
+    ///
+    /// ```
+    /// # use slots::unrestricted::UnrestrictedSlots;
+    /// # let mut slots: UnrestrictedSlots<_, 4> = UnrestrictedSlots::new();
+    /// let k = slots.store(2).unwrap();
+    ///
+    /// for (index, x) in slots.iter_indexed_mut() {
+    ///     *x += index;
+    /// }
+    ///
+    /// assert_eq!(Some(2 + k), slots.take(k));
+    /// ```
+    pub fn iter_indexed_mut(&mut self) -> IterIndexedMut<'_, IT> {
+        self.raw.iter_indexed_mut()
+    }
+
+    /// Removes every element and returns them, together with their indices, in an iterator.
+    ///
+    /// The collection is empty after the iterator is dropped, even if it was not fully consumed.
+    ///
+    /// **Note:** Do not rely on the order in which the elements are returned.
+    ///
+    /// ```
+    /// # use slots::unrestricted::UnrestrictedSlots;
+    /// # let mut slots: UnrestrictedSlots<_, 4> = UnrestrictedSlots::new();
+    /// let k = slots.store(2).unwrap();
+    /// slots.store(4).unwrap();
+    ///
+    /// let mut drained: Vec<_> = slots.drain().collect();
+    /// drained.sort_by_key(|&(_, item)| item);
+    ///
+    /// assert_eq!(0, slots.count());
+    /// assert_eq!((k, 2), drained[0]);
+    /// ```
+    pub fn drain(&mut self) -> Drain<'_, IT, N, I> {
+        Drain {
+            slots: self,
+            cursor: 0,
+        }
+    }
+
+    /// Drops every element, and releases all slots.
+    ///
+    /// If the destructor of an element panics, the remaining elements are leaked, and the
+    /// collection is left empty.
+    ///
+    /// ```
+    /// # use slots::unrestricted::UnrestrictedSlots;
+    /// # let mut slots: UnrestrictedSlots<_, 4> = UnrestrictedSlots::new();
+    /// slots.store(2).unwrap();
+    /// slots.store(4).unwrap();
+    ///
+    /// slots.clear();
+    ///
+    /// assert_eq!(0, slots.count());
+    /// assert_eq!(None, slots.iter().next());
+    /// ```
+    pub fn clear(&mut self) {
+        self.raw.clear();
+    }
+
+    /// Keeps only the elements for which `function` returns `true`, and drops the others.
+    ///
+    /// The callback receives the index and a mutable reference of every element.
+    ///
+    /// ```
+    /// # use slots::unrestricted::UnrestrictedSlots;
+    /// # let mut slots: UnrestrictedSlots<_, 4> = UnrestrictedSlots::new();
+    /// let k1 = slots.store(1).unwrap();
+    /// let k2 = slots.store(2).unwrap();
+    /// let k3 = slots.store(3).unwrap();
+    ///
+    /// slots.retain(|_, x| *x % 2 == 1);
+    ///
+    /// assert_eq!(Some(&1), slots.get(k1));
+    /// assert_eq!(None, slots.get(k2));
+    /// assert_eq!(Some(&3), slots.get(k3));
+    /// ```
+    pub fn retain(&mut self, mut function: impl FnMut(usize, &mut IT) -> bool) {
+        self.extract_if(|idx, item| !function(idx, item))
+            .for_each(drop);
+    }
+
+    /// Returns an iterator that removes the elements for which `filter` returns `true`, and
+    /// yields them together with their indices.
+    ///
+    /// The elements are removed lazily, when the iterator reaches them. Elements that are not
+    /// reached before the iterator is dropped are kept.
+    ///
+    /// ```
+    /// # use slots::unrestricted::UnrestrictedSlots;
+    /// # let mut slots: UnrestrictedSlots<_, 4> = UnrestrictedSlots::new();
+    /// let k1 = slots.store(1).unwrap();
+    /// let k2 = slots.store(2).unwrap();
+    ///
+    /// let expired: Vec<_> = slots.extract_if(|_, x| *x > 1).collect();
+    ///
+    /// assert_eq!(vec![(k2, 2)], expired);
+    /// assert_eq!(Some(&1), slots.get(k1));
+    /// ```
+    pub fn extract_if<F>(&mut self, filter: F) -> ExtractIf<'_, IT, N, I, F>
+    where
+        F: FnMut(usize, &mut IT) -> bool,
+    {
+        ExtractIf {
+            slots: self,
+            cursor: 0,
+            filter,
+        }
     }
 
     /// Returns the number of slots
@@ -144,7 +365,7 @@ impl<IT, const N: usize> UnrestrictedSlots<IT, N> {
     /// assert_eq!(2, slots.count());
     /// ```
     pub fn count(&self) -> usize {
-        self.count
+        self.raw.count()
     }
 
     /// Returns whether all the slots are occupied and the next [`store()`](UnrestrictedSlots::store) will fail.
@@ -164,38 +385,36 @@ impl<IT, const N: usize> UnrestrictedSlots<IT, N> {
     /// assert_eq!(true, slots.is_full());
     /// ```
     pub fn is_full(&self) -> bool {
-        self.count == self.capacity()
+        self.raw.is_full()
     }
 
-    fn free(&mut self, idx: usize) {
-        debug_assert!(self.count != 0, "Free called on an empty collection");
-
-        self.items[idx] = if self.is_full() {
-            Entry::EmptyLast
-        } else {
-            Entry::EmptyNext(self.next_free)
-        };
+    /// Places an element in a particular slot, see [`RawSlots::place`].
+    #[cfg(feature = "serde")]
+    pub(crate) fn place(&mut self, idx: usize, item: IT) -> Result<(), IT> {
+        self.raw.place(idx, item)
+    }
 
-        self.next_free = idx; // the freed element will always be the top of the free stack
-        self.count -= 1;
+    /// Lists a free slot that is handed out before the others, see [`RawSlots::place_released`].
+    #[cfg(feature = "serde")]
+    pub(crate) fn place_released(
+        &mut self,
+        released: &mut ReleasedSlots,
+        idx: usize,
+    ) -> Result<(), ()> {
+        self.raw.place_released(released, idx)
     }
 
-    fn alloc(&mut self) -> Option<usize> {
-        if self.is_full() {
-            // no free slot
-            None
-        } else {
-            // next_free points to the top of the free stack
-            let index = self.next_free;
+    /// Rebuilds the stack of released slots, see [`RawSlots::rebuild_free_list`].
+    #[cfg(feature = "serde")]
+    pub(crate) fn rebuild_free_list(&mut self, released: ReleasedSlots) {
+        self.raw.rebuild_free_list(released)
+    }
 
-            self.next_free = match self.items[index] {
-                Entry::EmptyNext(n) => n, // pop the stack
-                Entry::EmptyLast => 0,    // replace last element with anything
-                _ => unreachable!("Non-empty item in entry behind free chain"),
-            };
-            self.count += 1;
-            Some(index)
-        }
+    /// Returns the released slots in the order in which they are reused, see
+    /// [`RawSlots::released`].
+    #[cfg(feature = "serde")]
+    pub(crate) fn released(&self) -> impl Iterator<Item = usize> + '_ {
+        self.raw.released()
     }
 
     /// Store an element in a free slot and return the key to access it.
@@ -203,25 +422,77 @@ impl<IT, const N: usize> UnrestrictedSlots<IT, N> {
     /// Storing a variable takes ownership over it. If the storage is full,
     /// the inserted data is returned in the return value.
     pub fn store(&mut self, item: IT) -> Result<usize, IT> {
-        match self.alloc() {
-            Some(i) => {
-                self.items[i] = Entry::Used(item);
-                Ok(i)
-            }
-            None => Err(item),
-        }
+        self.raw.store(item)
+    }
+
+    /// Store an element in a free slot and return the key to access it.
+    ///
+    /// If the storage is full, the inserted data is returned in [`SlotsError::Full`].
+    ///
+    /// ```
+    /// # use slots::error::SlotsError;
+    /// # use slots::unrestricted::UnrestrictedSlots;
+    /// let mut slots: UnrestrictedSlots<_, 1> = UnrestrictedSlots::new();
+    ///
+    /// assert!(slots.try_store(3).is_ok());
+    /// assert_eq!(Err(SlotsError::Full(4)), slots.try_store(4));
+    /// ```
+    pub fn try_store(&mut self, item: IT) -> Result<usize, SlotsError<IT>> {
+        self.raw.store(item).map_err(SlotsError::Full)
+    }
+
+    /// Store an element created by `function` in a free slot, and return the key to access it.
+    ///
+    /// `function` receives the index that the element will be stored at, so the element can
+    /// refer to itself. If the storage is full, `function` is not called and `None` is returned.
+    /// If `function` panics, the collection is left unchanged.
+    ///
+    /// ```
+    /// # use slots::unrestricted::UnrestrictedSlots;
+    /// # let mut slots: UnrestrictedSlots<_, 4> = UnrestrictedSlots::new();
+    /// struct Timer {
+    ///     this: usize,
+    ///     period: u32,
+    /// }
+    ///
+    /// let k = slots.store_with(|this| Timer { this, period: 10 }).unwrap();
+    ///
+    /// assert_eq!(k, slots.get(k).unwrap().this);
+    /// ```
+    pub fn store_with(&mut self, function: impl FnOnce(usize) -> IT) -> Option<usize> {
+        let entry = self.vacant_entry()?;
+        let index = entry.index();
+
+        entry.insert(function(index));
+
+        Some(index)
     }
 
     /// Remove and return the element that belongs to the key.
     ///
-    /// This operation is fallible. If `key` addresses a free slot, `None` is returned.
+    /// This operation is fallible. If `key` addresses a free slot or is outside the collection,
+    /// `None` is returned.
     pub fn take(&mut self, key: usize) -> Option<IT> {
-        if let Entry::Used(item) = replace(&mut self.items[key], Entry::EmptyLast) {
-            self.free(key);
-            Some(item)
-        } else {
-            None
-        }
+        self.try_take(key).ok()
+    }
+
+    /// Remove and return the element that belongs to the key.
+    ///
+    /// This operation is fallible. If `key` addresses a free slot, [`SlotsError::Vacant`] is
+    /// returned. If `key` is outside the collection, [`SlotsError::OutOfBounds`] is returned.
+    ///
+    /// ```
+    /// # use slots::error::SlotsError;
+    /// # use slots::unrestricted::UnrestrictedSlots;
+    /// # let mut slots: UnrestrictedSlots<_, 4> = UnrestrictedSlots::new();
+    /// let k = slots.store(3).unwrap();
+    ///
+    /// assert_eq!(Ok(3), slots.try_take(k));
+    /// assert_eq!(Err(SlotsError::Vacant), slots.try_take(k));
+    /// assert_eq!(Err(SlotsError::OutOfBounds), slots.try_take(slots.capacity()));
+    /// ```
+    pub fn try_take(&mut self, key: usize) -> Result<IT, SlotsError<IT>> {
+        self.raw.take(key)
     }
 
     /// Read the element that belongs to a particular index. Since the index may point to
@@ -230,7 +501,8 @@ impl<IT, const N: usize> UnrestrictedSlots<IT, N> {
     /// This operation does not move ownership so the `function` callback must be used
     /// to access the stored element. The callback may return arbitrary derivative of the element.
     ///
-    /// This operation is fallible. If `key` addresses a free slot, `None` is returned.
+    /// This operation is fallible. If `key` addresses a free slot or is outside the collection,
+    /// `None` is returned.
     ///
     /// ```
     /// # use slots::unrestricted::UnrestrictedSlots;
@@ -249,14 +521,32 @@ impl<IT, const N: usize> UnrestrictedSlots<IT, N> {
     /// }));
     /// ```
     pub fn read<T>(&self, key: usize, function: impl FnOnce(&IT) -> T) -> Option<T> {
-        if key >= self.capacity() {
-            None
-        } else {
-            match self.items[key] {
-                Entry::Used(ref item) => Some(function(item)),
-                _ => None,
-            }
-        }
+        self.get(key).map(function)
+    }
+
+    /// Read the element that belongs to a particular index.
+    ///
+    /// This operation does not move ownership so the `function` callback must be used
+    /// to access the stored element. The callback may return arbitrary derivative of the element.
+    ///
+    /// This operation is fallible. If `key` addresses a free slot, [`SlotsError::Vacant`] is
+    /// returned. If `key` is outside the collection, [`SlotsError::OutOfBounds`] is returned.
+    ///
+    /// ```
+    /// # use slots::error::SlotsError;
+    /// # use slots::unrestricted::UnrestrictedSlots;
+    /// # let mut slots: UnrestrictedSlots<_, 4> = UnrestrictedSlots::new();
+    /// let k = slots.store(3).unwrap();
+    ///
+    /// assert_eq!(Ok(4), slots.try_read(k, |elem| elem + 1));
+    /// assert_eq!(Err(SlotsError::OutOfBounds), slots.try_read(4, |elem| elem + 1));
+    /// ```
+    pub fn try_read<T>(
+        &self,
+        key: usize,
+        function: impl FnOnce(&IT) -> T,
+    ) -> Result<T, SlotsError<IT>> {
+        self.raw.get(key).map(function)
     }
 
     /// Access the element that belongs to the key for modification.
@@ -264,7 +554,8 @@ impl<IT, const N: usize> UnrestrictedSlots<IT, N> {
     /// This operation does not move ownership so the `function` callback must be used
     /// to access the stored element. The callback may return arbitrary derivative of the element.
     ///
-    /// This operation is fallible. If `key` addresses a free slot, `None` is returned.
+    /// This operation is fallible. If `key` addresses a free slot or is outside the collection,
+    /// `None` is returned.
     ///
     /// ```
     /// # use slots::unrestricted::UnrestrictedSlots;
@@ -288,9 +579,365 @@ impl<IT, const N: usize> UnrestrictedSlots<IT, N> {
     /// }));
     /// ```
     pub fn modify<T>(&mut self, key: usize, function: impl FnOnce(&mut IT) -> T) -> Option<T> {
-        match self.items[key] {
-            Entry::Used(ref mut item) => Some(function(item)),
-            _ => None,
+        self.get_mut(key).map(function)
+    }
+
+    /// Access the element that belongs to the key for modification.
+    ///
+    /// This operation does not move ownership so the `function` callback must be used
+    /// to access the stored element. The callback may return arbitrary derivative of the element.
+    ///
+    /// This operation is fallible. If `key` addresses a free slot, [`SlotsError::Vacant`] is
+    /// returned. If `key` is outside the collection, [`SlotsError::OutOfBounds`] is returned.
+    ///
+    /// ```
+    /// # use slots::error::SlotsError;
+    /// # use slots::unrestricted::UnrestrictedSlots;
+    /// # let mut slots: UnrestrictedSlots<_, 4> = UnrestrictedSlots::new();
+    /// let k = slots.store(3).unwrap();
+    ///
+    /// assert_eq!(Ok(()), slots.try_modify(k, |elem| *elem += 1));
+    /// assert_eq!(Some(4), slots.take(k));
+    /// assert_eq!(Err(SlotsError::Vacant), slots.try_modify(k, |elem| *elem += 1));
+    /// ```
+    pub fn try_modify<T>(
+        &mut self,
+        key: usize,
+        function: impl FnOnce(&mut IT) -> T,
+    ) -> Result<T, SlotsError<IT>> {
+        self.raw.get_mut(key).map(function)
+    }
+
+    /// Returns a reference to the element that belongs to the key.
+    ///
+    /// This operation is fallible. If `key` addresses a free slot or is outside the collection,
+    /// `None` is returned.
+    ///
+    /// ```
+    /// # use slots::unrestricted::UnrestrictedSlots;
+    /// # let mut slots: UnrestrictedSlots<_, 4> = UnrestrictedSlots::new();
+    ///
+    /// let k = slots.store(3).unwrap();
+    ///
+    /// assert_eq!(Some(&3), slots.get(k));
+    ///
+    /// slots.take(k);
+    ///
+    /// assert_eq!(None, slots.get(k));
+    /// assert_eq!(None, slots.get(slots.capacity()));
+    /// ```
+    pub fn get(&self, key: usize) -> Option<&IT> {
+        self.raw.get(key).ok()
+    }
+
+    /// Returns a mutable reference to the element that belongs to the key.
+    ///
+    /// This operation is fallible. If `key` addresses a free slot or is outside the collection,
+    /// `None` is returned.
+    ///
+    /// ```
+    /// # use slots::unrestricted::UnrestrictedSlots;
+    /// # let mut slots: UnrestrictedSlots<_, 4> = UnrestrictedSlots::new();
+    ///
+    /// let k = slots.store(3).unwrap();
+    ///
+    /// if let Some(elem) = slots.get_mut(k) {
+    ///     *elem += 1;
+    /// }
+    ///
+    /// assert_eq!(Some(4), slots.take(k));
+    /// assert_eq!(None, slots.get_mut(k));
+    /// ```
+    pub fn get_mut(&mut self, key: usize) -> Option<&mut IT> {
+        self.raw.get_mut(key).ok()
+    }
+
+    /// Returns mutable references to multiple elements at once.
+    ///
+    /// This operation is fallible. If any of the `keys` address a free slot or are outside the
+    /// collection, or if the same key is present more than once, `None` is returned.
+    ///
+    /// ```
+    /// # use slots::unrestricted::UnrestrictedSlots;
+    /// # let mut slots: UnrestrictedSlots<_, 4> = UnrestrictedSlots::new();
+    ///
+    /// let k1 = slots.store(3).unwrap();
+    /// let k2 = slots.store(5).unwrap();
+    ///
+    /// if let Some([a, b]) = slots.get_many_mut([k1, k2]) {
+    ///     core::mem::swap(a, b);
+    /// }
+    ///
+    /// assert_eq!(Some(&5), slots.get(k1));
+    /// assert_eq!(Some(&3), slots.get(k2));
+    ///
+    /// // The same slot can't be borrowed twice
+    /// assert!(slots.get_many_mut([k1, k1]).is_none());
+    /// ```
+    pub fn get_many_mut<const M: usize>(&mut self, keys: [usize; M]) -> Option<[&mut IT; M]> {
+        self.raw.get_many_mut(keys)
+    }
+
+    /// Returns an entry for the slot that the next element will be stored in, or `None` if the
+    /// collection is full.
+    ///
+    /// The index is known before the element is created, so the element can refer to itself.
+    /// Dropping the entry without inserting leaves the collection unchanged.
+    ///
+    /// ```
+    /// # use slots::unrestricted::UnrestrictedSlots;
+    /// # let mut slots: UnrestrictedSlots<_, 4> = UnrestrictedSlots::new();
+    /// struct Node {
+    ///     this: usize,
+    /// }
+    ///
+    /// let entry = slots.vacant_entry().unwrap();
+    /// let this = entry.index();
+    /// entry.insert(Node { this });
+    ///
+    /// assert_eq!(this, slots.get(this).unwrap().this);
+    /// ```
+    pub fn vacant_entry(&mut self) -> Option<VacantEntry<'_, IT, N, I>> {
+        let index = self.raw.peek_free()?;
+
+        Some(VacantEntry { slots: self, index })
+    }
+
+    /// Returns an entry for an occupied slot, or `None` if `key` addresses a free slot or is
+    /// outside the collection.
+    ///
+    /// ```
+    /// # use slots::unrestricted::UnrestrictedSlots;
+    /// # let mut slots: UnrestrictedSlots<_, 4> = UnrestrictedSlots::new();
+    /// let k = slots.store(3).unwrap();
+    ///
+    /// let mut entry = slots.occupied_entry(k).unwrap();
+    /// *entry.get_mut() += 1;
+    /// assert_eq!(4, entry.insert(5));
+    /// assert_eq!(5, entry.remove());
+    ///
+    /// assert!(slots.occupied_entry(k).is_none());
+    /// ```
+    pub fn occupied_entry(&mut self, key: usize) -> Option<OccupiedEntry<'_, IT, N, I>> {
+        self.raw.get(key).ok()?;
+
+        Some(OccupiedEntry {
+            slots: self,
+            index: key,
+        })
+    }
+}
+
+impl<'a, IT, const N: usize, I: SlotIndex> IntoIterator for &'a UnrestrictedSlots<IT, N, I> {
+    type Item = &'a IT;
+    type IntoIter = Iter<'a, IT>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, IT, const N: usize, I: SlotIndex> IntoIterator for &'a mut UnrestrictedSlots<IT, N, I> {
+    type Item = &'a mut IT;
+    type IntoIter = IterMut<'a, IT>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<IT, const N: usize, I: SlotIndex> IntoIterator for UnrestrictedSlots<IT, N, I> {
+    type Item = (usize, IT);
+    type IntoIter = IntoIter<IT, N, I>;
+
+    /// Consumes the collection, and returns its elements together with their indices.
+    ///
+    /// ```
+    /// # use slots::unrestricted::UnrestrictedSlots;
+    /// # let mut slots: UnrestrictedSlots<_, 4> = UnrestrictedSlots::new();
+    /// let k = slots.store(2).unwrap();
+    ///
+    /// assert_eq!(vec![(k, 2)], slots.into_iter().collect::<Vec<_>>());
+    /// ```
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter {
+            slots: self,
+            cursor: 0,
         }
     }
 }
+
+/// Iterator that removes every element of an [`UnrestrictedSlots`] object.
+///
+/// Created by [`UnrestrictedSlots::drain`]. Elements that are not consumed are dropped together
+/// with the iterator.
+pub struct Drain<'a, IT, const N: usize, I: SlotIndex = usize> {
+    slots: &'a mut UnrestrictedSlots<IT, N, I>,
+    /// Slots before the cursor are free.
+    cursor: usize,
+}
+
+impl<IT, const N: usize, I: SlotIndex> Iterator for Drain<'_, IT, N, I> {
+    type Item = (usize, IT);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.slots.raw.take_next(&mut self.cursor)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let count = self.slots.count();
+        (count, Some(count))
+    }
+}
+
+impl<IT, const N: usize, I: SlotIndex> ExactSizeIterator for Drain<'_, IT, N, I> {}
+
+impl<IT, const N: usize, I: SlotIndex> FusedIterator for Drain<'_, IT, N, I> {}
+
+impl<IT, const N: usize, I: SlotIndex> Drop for Drain<'_, IT, N, I> {
+    fn drop(&mut self) {
+        self.slots.raw.clear();
+    }
+}
+
+/// Iterator that moves the elements out of an [`UnrestrictedSlots`] object.
+///
+/// Created by the [`IntoIterator`] implementation of [`UnrestrictedSlots`].
+pub struct IntoIter<IT, const N: usize, I: SlotIndex = usize> {
+    slots: UnrestrictedSlots<IT, N, I>,
+    /// Slots before the cursor are free.
+    cursor: usize,
+}
+
+impl<IT, const N: usize, I: SlotIndex> Iterator for IntoIter<IT, N, I> {
+    type Item = (usize, IT);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.slots.raw.take_next(&mut self.cursor)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let count = self.slots.count();
+        (count, Some(count))
+    }
+}
+
+impl<IT, const N: usize, I: SlotIndex> ExactSizeIterator for IntoIter<IT, N, I> {}
+
+impl<IT, const N: usize, I: SlotIndex> FusedIterator for IntoIter<IT, N, I> {}
+
+/// Iterator that removes the elements of an [`UnrestrictedSlots`] object that match a filter.
+///
+/// Created by [`UnrestrictedSlots::extract_if`].
+pub struct ExtractIf<'a, IT, const N: usize, I: SlotIndex, F> {
+    slots: &'a mut UnrestrictedSlots<IT, N, I>,
+    /// Slots before the cursor have been visited.
+    cursor: usize,
+    filter: F,
+}
+
+impl<IT, const N: usize, I: SlotIndex, F> Iterator for ExtractIf<'_, IT, N, I, F>
+where
+    F: FnMut(usize, &mut IT) -> bool,
+{
+    type Item = (usize, IT);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(idx) = self.slots.raw.next_occupied(self.cursor) {
+            self.cursor = idx + 1;
+
+            let item = self.slots.raw.get_mut(idx).ok()?;
+            if (self.filter)(idx, item) {
+                // taking the element pushes its slot on the stack of released slots
+                return self.slots.raw.take(idx).ok().map(|item| (idx, item));
+            }
+        }
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.slots.count()))
+    }
+}
+
+impl<IT, const N: usize, I: SlotIndex, F> FusedIterator for ExtractIf<'_, IT, N, I, F> where
+    F: FnMut(usize, &mut IT) -> bool
+{
+}
+
+/// A free slot of an [`UnrestrictedSlots`] object.
+///
+/// Created by [`UnrestrictedSlots::vacant_entry`].
+pub struct VacantEntry<'a, IT, const N: usize, I: SlotIndex = usize> {
+    slots: &'a mut UnrestrictedSlots<IT, N, I>,
+    index: usize,
+}
+
+impl<'a, IT, const N: usize, I: SlotIndex> VacantEntry<'a, IT, N, I> {
+    /// Returns the index that the element will be stored at.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Stores an element in the slot, and returns a reference to it.
+    pub fn insert(self, item: IT) -> &'a mut IT {
+        match self.slots.raw.store_mut(item) {
+            Ok((index, item)) => {
+                debug_assert_eq!(
+                    index, self.index,
+                    "Vacant entry stored at a different index"
+                );
+                item
+            }
+            Err(_) => unreachable!("The slot of a vacant entry is free"),
+        }
+    }
+}
+
+/// An occupied slot of an [`UnrestrictedSlots`] object.
+///
+/// Created by [`UnrestrictedSlots::occupied_entry`].
+pub struct OccupiedEntry<'a, IT, const N: usize, I: SlotIndex = usize> {
+    slots: &'a mut UnrestrictedSlots<IT, N, I>,
+    index: usize,
+}
+
+impl<'a, IT, const N: usize, I: SlotIndex> OccupiedEntry<'a, IT, N, I> {
+    /// Returns the index of the element.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Returns a reference to the element.
+    pub fn get(&self) -> &IT {
+        self.slots.get(self.index).expect("Occupied entry is empty")
+    }
+
+    /// Returns a mutable reference to the element.
+    pub fn get_mut(&mut self) -> &mut IT {
+        self.slots
+            .get_mut(self.index)
+            .expect("Occupied entry is empty")
+    }
+
+    /// Converts the entry into a mutable reference to the element, that lives as long as the
+    /// borrow of the collection.
+    pub fn into_mut(self) -> &'a mut IT {
+        self.slots
+            .get_mut(self.index)
+            .expect("Occupied entry is empty")
+    }
+
+    /// Replaces the element, and returns the previous one.
+    pub fn insert(&mut self, item: IT) -> IT {
+        core::mem::replace(self.get_mut(), item)
+    }
+
+    /// Removes the element from the collection, and returns it.
+    pub fn remove(self) -> IT {
+        self.slots
+            .take(self.index)
+            .expect("Occupied entry is empty")
+    }
+}
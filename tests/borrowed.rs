@@ -0,0 +1,77 @@
+use core::mem::MaybeUninit;
+use std::rc::Rc;
+
+use slots::borrowed::{BorrowedSlots, BorrowedUnrestrictedSlots, Slot};
+use slots::error::SlotsError;
+
+#[test]
+fn capacity_is_taken_from_the_buffer() {
+    let mut buffer = [const { MaybeUninit::<Slot<u32, u8>>::uninit() }; 5];
+    let mut slots = BorrowedUnrestrictedSlots::new(&mut buffer[..3]);
+
+    assert_eq!(3, slots.capacity());
+
+    let keys: Vec<_> = (0..3).map(|i| slots.store(i).unwrap()).collect();
+    assert!(slots.is_full());
+    assert_eq!(Err(3), slots.store(3));
+
+    assert_eq!(Some(1), slots.take(keys[1]));
+    assert_eq!(Err(SlotsError::Vacant), slots.try_take(keys[1]));
+    assert_eq!(Err(SlotsError::OutOfBounds), slots.try_take(3));
+
+    let k = slots.store(10).unwrap();
+    assert_eq!(keys[1], k);
+    assert_eq!(Some(11), slots.modify(k, |e| *e + 1));
+}
+
+#[test]
+fn keys_are_verified() {
+    let mut buffer = [const { MaybeUninit::<Slot<u32>>::uninit() }; 4];
+    let mut slots: BorrowedSlots<u32> = BorrowedSlots::new(&mut buffer);
+
+    let k = slots.store(5).unwrap();
+    slots[&k] += 1;
+    assert_eq!(Some(6), slots.try_read(k.index(), |&e| e));
+    assert_eq!(6, slots.take(k));
+}
+
+#[test]
+#[cfg(feature = "runtime_checks")]
+fn keys_of_other_instances_are_rejected() {
+    let mut buffer_a = [const { MaybeUninit::<Slot<u32>>::uninit() }; 4];
+    let mut buffer_b = [const { MaybeUninit::<Slot<u32>>::uninit() }; 4];
+    let mut a: BorrowedSlots<u32> = BorrowedSlots::new(&mut buffer_a);
+    let mut b: BorrowedSlots<u32> = BorrowedSlots::new(&mut buffer_b);
+
+    let k = a.store(5).unwrap();
+    assert_eq!(
+        Err(SlotsError::WrongOwner),
+        b.try_take(k).map_err(|e| e.error)
+    );
+}
+
+#[test]
+fn stored_elements_are_dropped_with_the_collection() {
+    let item = Rc::new(());
+    let mut buffer = [const { MaybeUninit::<Slot<Rc<()>>>::uninit() }; 4];
+
+    {
+        let mut slots = BorrowedUnrestrictedSlots::new(&mut buffer);
+        slots.store(item.clone()).unwrap();
+        slots.store(item.clone()).unwrap();
+        assert_eq!(3, Rc::strong_count(&item));
+    }
+    assert_eq!(1, Rc::strong_count(&item));
+
+    // the buffer can be reused
+    let mut slots = BorrowedUnrestrictedSlots::new(&mut buffer);
+    assert_eq!(0, slots.count());
+    slots.store(item.clone()).unwrap();
+}
+
+#[test]
+#[should_panic(expected = "The capacity can not be represented by the index type")]
+fn buffer_length_must_fit_index_type() {
+    let mut buffer = [const { MaybeUninit::<Slot<(), u8>>::uninit() }; 256];
+    let _slots = BorrowedUnrestrictedSlots::new(&mut buffer);
+}
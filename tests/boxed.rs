@@ -0,0 +1,95 @@
+#![cfg(feature = "alloc")]
+
+use slots::boxed::{BoxSlots, Key};
+use slots::error::SlotsError;
+
+#[test]
+fn key_can_be_used_to_access_value() {
+    let mut slots: BoxSlots<u32> = BoxSlots::with_capacity(8);
+    let k = slots.store(5).unwrap();
+
+    assert_eq!(5, slots.read(&k, |&w| w));
+    slots.modify(&k, |w| *w += 1);
+    slots[&k] *= 2;
+    assert_eq!(Some(12), slots.try_read(k.index(), |&w| w));
+    assert_eq!(12, slots.take(k));
+    assert_eq!(0, slots.count());
+}
+
+#[test]
+fn capacity_is_chosen_at_runtime() {
+    for capacity in [0, 1, 7, 100] {
+        let mut slots: BoxSlots<usize> = BoxSlots::with_capacity(capacity);
+
+        assert_eq!(capacity, slots.capacity());
+
+        let keys: Vec<_> = (0..capacity).map(|i| slots.store(i).unwrap()).collect();
+        assert!(slots.is_full());
+        assert_eq!(Err(SlotsError::Full(0)), slots.try_store(0).map(|_| ()));
+
+        let mut indices: Vec<_> = keys.iter().map(Key::index).collect();
+        indices.sort_unstable();
+        assert_eq!((0..capacity).collect::<Vec<_>>(), indices);
+
+        for (i, k) in keys.into_iter().enumerate() {
+            assert_eq!(i, slots.take(k));
+        }
+        assert_eq!(0, slots.count());
+    }
+}
+
+#[test]
+fn released_slots_are_reused() {
+    let mut slots: BoxSlots<u32> = BoxSlots::with_capacity(2);
+
+    let k1 = slots.store(1).unwrap();
+    let _k2 = slots.store(2).unwrap();
+    let index = k1.index();
+    slots.take(k1);
+
+    let k3 = slots.store(3).unwrap();
+    assert_eq!(index, k3.index());
+}
+
+#[test]
+#[should_panic(expected = "The capacity can not be represented by the index type")]
+fn capacity_must_fit_index_type() {
+    let _slots: BoxSlots<u32, u8> = BoxSlots::with_capacity(256);
+}
+
+#[test]
+#[cfg(feature = "runtime_checks")]
+#[should_panic(expected = "Key used in wrong instance")]
+fn use_across_slots_verify() {
+    let mut a: BoxSlots<u8> = BoxSlots::with_capacity(4);
+    let mut b: BoxSlots<u8> = BoxSlots::with_capacity(4);
+
+    let k = a.store(5).unwrap();
+    let _ = b.store(6).unwrap();
+
+    b.take(k);
+}
+
+#[test]
+#[cfg(feature = "runtime_checks")]
+fn try_methods_report_wrong_owner() {
+    let mut a: BoxSlots<u8> = BoxSlots::with_capacity(4);
+    let mut b: BoxSlots<u8> = BoxSlots::with_capacity(4);
+
+    let k = a.store(5).unwrap();
+
+    assert_eq!(Err(SlotsError::WrongOwner), b.try_read_key(&k, |&e| e));
+    assert_eq!(Err(SlotsError::WrongOwner), b.try_modify(&k, |e| *e));
+    assert_eq!(
+        Err(SlotsError::WrongOwner),
+        b.try_take(k).map_err(|e| e.error)
+    );
+}
+
+#[test]
+fn option_key_is_not_larger_than_key() {
+    assert_eq!(
+        core::mem::size_of::<Key<u32>>(),
+        core::mem::size_of::<Option<Key<u32>>>()
+    );
+}
@@ -0,0 +1,41 @@
+use slots::branded::{BrandedSlots, Key};
+
+#[test]
+fn key_can_be_used_to_access_value() {
+    BrandedSlots::<_, 8>::scope(|mut slots| {
+        let k = slots.store(5).unwrap();
+
+        assert_eq!(5, slots.read(&k, |&w| w));
+        slots.modify(&k, |w| *w += 1);
+        slots[&k] *= 2;
+        assert_eq!(Some(12), slots.try_read(k.index(), |&w| w));
+        assert_eq!(12, slots.take(k));
+        assert_eq!(0, slots.count());
+    });
+}
+
+#[test]
+fn store_returns_err_when_full() {
+    BrandedSlots::<_, 1>::scope(|mut slots| {
+        let _k = slots.store(5).unwrap();
+
+        assert!(slots.is_full());
+        assert_eq!(Err(6), slots.store(6).map(|_| ()));
+    });
+}
+
+#[test]
+fn keys_are_not_larger_than_index() {
+    assert_eq!(
+        core::mem::size_of::<u8>(),
+        core::mem::size_of::<Option<Key<'static, u32, 8, u8>>>()
+    );
+}
+
+#[test]
+fn collection_is_not_larger_than_unrestricted() {
+    assert_eq!(
+        core::mem::size_of::<slots::unrestricted::UnrestrictedSlots<u32, 8>>(),
+        core::mem::size_of::<BrandedSlots<'static, u32, 8>>()
+    );
+}
@@ -0,0 +1,120 @@
+#![cfg(not(loom))]
+
+use std::sync::{mpsc, Arc};
+use std::thread;
+
+use slots::concurrent::ConcurrentSlots;
+
+#[test]
+fn key_can_be_used_to_read_value() {
+    let slots: ConcurrentSlots<_, 8> = ConcurrentSlots::new();
+    let k1 = slots.store(5).unwrap();
+
+    assert_eq!(5, slots.read(&k1, |&w| w));
+}
+
+#[test]
+fn store_returns_err_when_full() {
+    let slots: ConcurrentSlots<u8, 1> = ConcurrentSlots::new();
+
+    let k1 = slots.store(5).unwrap();
+    assert_eq!(Err(6), slots.store(6).map(|k| k.index()));
+
+    slots.take(k1);
+    assert!(slots.store(6).is_ok());
+}
+
+#[test]
+fn zero_sized_collection() {
+    let slots: ConcurrentSlots<u8, 0> = ConcurrentSlots::new();
+
+    assert_eq!(0, slots.capacity());
+    assert!(slots.store(1).is_err());
+}
+
+#[test]
+#[should_panic(expected = "Key used in wrong instance")]
+fn use_across_slots_verify() {
+    let a: ConcurrentSlots<u8, 4> = ConcurrentSlots::new();
+    let b: ConcurrentSlots<u8, 4> = ConcurrentSlots::new();
+
+    let k = a.store(5).unwrap();
+    let _ = b.store(6).unwrap();
+
+    b.take(k);
+}
+
+#[test]
+fn remaining_elements_are_dropped() {
+    let item = Arc::new(());
+    {
+        let slots: ConcurrentSlots<_, 4> = ConcurrentSlots::new();
+
+        let k = slots.store(item.clone()).unwrap();
+        slots.store(item.clone()).unwrap();
+        slots.store(item.clone()).unwrap();
+        drop(slots.take(k));
+
+        assert_eq!(3, Arc::strong_count(&item));
+    }
+    assert_eq!(1, Arc::strong_count(&item));
+}
+
+#[test]
+fn producers_and_consumers_on_multiple_threads() {
+    const PRODUCERS: usize = 4;
+    const ITEMS: usize = 1000;
+
+    let slots: Arc<ConcurrentSlots<usize, 16>> = Arc::new(ConcurrentSlots::new());
+    let (sender, receiver) = mpsc::channel();
+
+    let producers = (0..PRODUCERS)
+        .map(|p| {
+            let slots = slots.clone();
+            let sender = sender.clone();
+            thread::spawn(move || {
+                for i in 0..ITEMS {
+                    let mut item = p * ITEMS + i;
+                    loop {
+                        match slots.store(item) {
+                            Ok(key) => break sender.send(key).unwrap(),
+                            Err(returned) => {
+                                item = returned;
+                                thread::yield_now();
+                            }
+                        }
+                    }
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    drop(sender);
+
+    let mut received = receiver
+        .into_iter()
+        .map(|key| slots.take(key))
+        .collect::<Vec<_>>();
+
+    for producer in producers {
+        producer.join().unwrap();
+    }
+
+    received.sort_unstable();
+    assert_eq!((0..PRODUCERS * ITEMS).collect::<Vec<_>>(), received);
+    assert_eq!(0, slots.count());
+}
+
+#[test]
+#[should_panic(expected = "Key used in wrong instance")]
+fn keys_are_shared_with_slots_but_verified() {
+    use slots::owner::AtomicOwnerId;
+    use slots::slots::{Key, Slots};
+
+    let mut a: Slots<u8, 4, usize, AtomicOwnerId> = Slots::new();
+    let b: ConcurrentSlots<u8, 4> = ConcurrentSlots::new();
+
+    let k: Key<u8, 4, usize, AtomicOwnerId> = a.store(5).unwrap();
+    let _ = b.store(6).unwrap();
+
+    b.take(k);
+}
@@ -0,0 +1,78 @@
+use std::rc::Rc;
+
+use slots::dense::DenseSlots;
+use slots::error::SlotsError;
+
+#[test]
+fn indices_remain_valid_when_elements_are_moved() {
+    let mut slots: DenseSlots<_, 8, u8> = DenseSlots::new();
+
+    let keys: Vec<usize> = (0..8).map(|i| slots.store(i).unwrap()).collect();
+    assert!(slots.is_full());
+    assert_eq!(Err(8), slots.store(8));
+
+    for &key in keys.iter().step_by(2) {
+        assert!(slots.take(key).is_some());
+    }
+
+    assert_eq!(4, slots.count());
+    assert_eq!(4, slots.as_slice().len());
+    for (i, &key) in keys.iter().enumerate() {
+        let expected = if i % 2 == 0 { None } else { Some(&i) };
+        assert_eq!(expected, slots.get(key));
+    }
+
+    // released slots are reused
+    let k = slots.store(100).unwrap();
+    assert_eq!(Some(&100), slots.get(k));
+    assert_eq!(5, slots.as_slice().len());
+}
+
+#[test]
+fn slice_and_indices_agree() {
+    let mut slots: DenseSlots<_, 16> = DenseSlots::new();
+
+    let keys: Vec<usize> = (0..10).map(|i| slots.store(i * 10).unwrap()).collect();
+    slots.take(keys[3]);
+    slots.take(keys[9]);
+    slots.take(keys[0]);
+
+    for position in 0..slots.count() {
+        let key = slots.index_at(position).unwrap();
+        assert_eq!(Some(&slots.as_slice()[position]), slots.get(key));
+    }
+    assert_eq!(None, slots.index_at(slots.count()));
+
+    for item in slots.iter_mut() {
+        *item += 1;
+    }
+    assert_eq!(Some(21), slots.take(keys[2]));
+}
+
+#[test]
+fn errors_are_reported() {
+    let mut slots: DenseSlots<_, 1> = DenseSlots::new();
+
+    let k = slots.try_store(3).unwrap();
+    assert_eq!(Err(SlotsError::Full(4)), slots.try_store(4));
+    assert_eq!(Ok(4), slots.try_modify(k, |e| *e + 1));
+    assert_eq!(Ok(3), slots.try_take(k));
+    assert_eq!(Err(SlotsError::Vacant), slots.try_read(k, |&e| e));
+    assert_eq!(Err(SlotsError::OutOfBounds), slots.try_take(1));
+}
+
+#[test]
+fn elements_are_dropped_exactly_once() {
+    let item = Rc::new(());
+    {
+        let mut slots: DenseSlots<_, 4> = DenseSlots::new();
+
+        let k1 = slots.store(item.clone()).unwrap();
+        slots.store(item.clone()).unwrap();
+        slots.store(item.clone()).unwrap();
+
+        drop(slots.take(k1));
+        assert_eq!(3, Rc::strong_count(&item));
+    }
+    assert_eq!(1, Rc::strong_count(&item));
+}
@@ -0,0 +1,130 @@
+use slots::generational::GenerationalSlots;
+
+#[test]
+fn handle_can_be_used_to_read_value() {
+    let mut slots: GenerationalSlots<_, 8> = GenerationalSlots::new();
+    let h1 = slots.store(5).unwrap();
+
+    assert_eq!(Some(5), slots.read(h1, |&w| w));
+}
+
+#[test]
+fn stale_handle_is_rejected_after_slot_reuse() {
+    let mut slots: GenerationalSlots<u8, 1> = GenerationalSlots::new();
+
+    let h1 = slots.store(5).unwrap();
+    assert_eq!(Some(5), slots.take(h1));
+
+    let h2 = slots.store(6).unwrap();
+    assert_eq!(h1.index(), h2.index());
+    assert_ne!(h1, h2);
+
+    assert_eq!(None, slots.read(h1, |&w| w));
+    assert_eq!(None, slots.modify(h1, |w| *w = 0));
+    assert_eq!(None, slots.take(h1));
+
+    // the element stored through the new handle is untouched
+    assert_eq!(1, slots.count());
+    assert_eq!(Some(6), slots.take(h2));
+}
+
+#[test]
+fn handle_can_only_be_taken_once() {
+    let mut slots: GenerationalSlots<u8, 4> = GenerationalSlots::new();
+
+    let h = slots.store(5).unwrap();
+
+    assert_eq!(Some(5), slots.take(h));
+    assert_eq!(None, slots.take(h));
+    assert_eq!(0, slots.count());
+}
+
+#[test]
+fn handle_from_larger_collection_is_rejected() {
+    let mut a: GenerationalSlots<u8, 8> = GenerationalSlots::new();
+    let b: GenerationalSlots<u8, 1> = GenerationalSlots::new();
+
+    let h = a.store(5).unwrap();
+
+    assert!(h.index() >= b.capacity());
+    assert_eq!(None, b.read(h, |&w| w));
+}
+
+#[test]
+fn zero_sized_collection() {
+    let mut slots: GenerationalSlots<u8, 0> = GenerationalSlots::new();
+
+    assert_eq!(slots.capacity(), 0);
+    assert!(slots.store(1).is_err());
+}
+
+#[test]
+fn clear_makes_handles_stale() {
+    let mut slots: GenerationalSlots<u8, 2> = GenerationalSlots::new();
+
+    let h1 = slots.store(5).unwrap();
+    let h2 = slots.store(6).unwrap();
+    slots.clear();
+
+    assert_eq!(0, slots.count());
+
+    // the slots are reused, but the handles belong to an earlier generation
+    let h3 = slots.store(7).unwrap();
+    let h4 = slots.store(8).unwrap();
+    assert!(!slots.contains(h1));
+    assert!(!slots.contains(h2));
+    assert_eq!(Some(7), slots.take(h3));
+    assert_eq!(Some(8), slots.take(h4));
+}
+
+#[test]
+fn drain_makes_handles_stale() {
+    let mut slots: GenerationalSlots<u8, 4> = GenerationalSlots::new();
+
+    let h1 = slots.store(5).unwrap();
+    let h2 = slots.store(6).unwrap();
+
+    let mut drain = slots.drain();
+    assert_eq!(2, drain.len());
+    assert!(drain.next().is_some());
+    drop(drain);
+
+    assert_eq!(0, slots.count());
+    assert_eq!(None, slots.take(h1));
+    assert_eq!(None, slots.take(h2));
+}
+
+#[test]
+fn iter_indexed_returns_the_index_of_every_handle() {
+    let mut slots: GenerationalSlots<u8, 4> = GenerationalSlots::new();
+
+    let h1 = slots.store(5).unwrap();
+    let h2 = slots.store(6).unwrap();
+    let h3 = slots.store(7).unwrap();
+    slots.take(h2);
+
+    let mut items: Vec<_> = slots.iter_indexed().collect();
+    items.sort();
+    let mut expected = vec![(h1.index(), &5), (h3.index(), &7)];
+    expected.sort();
+    assert_eq!(expected, items);
+}
+
+#[test]
+fn generations_wrap_around_at_the_generation_type() {
+    let mut slots: GenerationalSlots<u8, 1, u8> = GenerationalSlots::new();
+
+    assert_eq!(2, core::mem::size_of_val(&slots.store(0).unwrap()));
+    slots.clear();
+
+    let stale = slots.store(1).unwrap();
+    slots.take(stale);
+    for _ in 0..u8::MAX {
+        assert!(!slots.contains(stale));
+        let h = slots.store(2).unwrap();
+        slots.take(h);
+    }
+
+    let h = slots.store(3).unwrap();
+    assert_eq!(stale, h);
+}
@@ -0,0 +1,116 @@
+//! Model checked tests of the concurrent collections.
+//!
+//! Run with `RUSTFLAGS="--cfg loom" cargo test --release --test loom`
+#![cfg(loom)]
+
+use loom::sync::Arc;
+use loom::thread;
+
+use slots::concurrent::ConcurrentSlots;
+
+#[test]
+fn concurrent_stores_use_different_slots() {
+    loom::model(|| {
+        let slots: Arc<ConcurrentSlots<usize, 2>> = Arc::new(ConcurrentSlots::new());
+
+        let threads = (0..2)
+            .map(|i| {
+                let slots = slots.clone();
+                thread::spawn(move || slots.store(i).unwrap())
+            })
+            .collect::<Vec<_>>();
+
+        let keys = threads
+            .into_iter()
+            .map(|t| t.join().unwrap())
+            .collect::<Vec<_>>();
+
+        assert_ne!(keys[0].index(), keys[1].index());
+        assert!(slots.is_full());
+
+        let mut values = keys.into_iter().map(|k| slots.take(k)).collect::<Vec<_>>();
+        values.sort_unstable();
+        assert_eq!(vec![0, 1], values);
+    });
+}
+
+#[test]
+fn store_fails_only_when_full() {
+    loom::model(|| {
+        let slots: Arc<ConcurrentSlots<usize, 1>> = Arc::new(ConcurrentSlots::new());
+
+        let other = {
+            let slots = slots.clone();
+            thread::spawn(move || slots.store(1).ok())
+        };
+        let mine = slots.store(2).ok();
+        let other = other.join().unwrap();
+
+        // exactly one of the stores succeeds
+        assert!(mine.is_some() != other.is_some());
+    });
+}
+
+#[test]
+fn take_and_store_on_different_threads() {
+    loom::model(|| {
+        let slots: Arc<ConcurrentSlots<usize, 2>> = Arc::new(ConcurrentSlots::new());
+
+        let k1 = slots.store(1).unwrap();
+        let k2 = slots.store(2).unwrap();
+
+        let taker = {
+            let slots = slots.clone();
+            thread::spawn(move || slots.take(k1))
+        };
+        let storer = {
+            let slots = slots.clone();
+            thread::spawn(move || slots.store(3).ok())
+        };
+
+        assert_eq!(1, taker.join().unwrap());
+        if let Some(k3) = storer.join().unwrap() {
+            assert_eq!(3, slots.read(&k3, |&v| v));
+            assert_eq!(3, slots.take(k3));
+        }
+        assert_eq!(2, slots.take(k2));
+        assert_eq!(0, slots.count());
+    });
+}
+
+#[test]
+fn pop_is_protected_against_aba() {
+    loom::model(|| {
+        let slots: Arc<ConcurrentSlots<usize, 3>> = Arc::new(ConcurrentSlots::new());
+
+        // While one thread is about to pop the top of the free stack, another one pops two
+        // slots and pushes the first one back. The first thread must not restore the second
+        // slot as the top of the stack.
+        let storer = {
+            let slots = slots.clone();
+            thread::spawn(move || slots.store(0).unwrap())
+        };
+        let recycler = {
+            let slots = slots.clone();
+            thread::spawn(move || {
+                let k1 = slots.store(1).unwrap();
+                let k2 = slots.store(2).unwrap();
+                slots.take(k1);
+                k2
+            })
+        };
+
+        let mut keys = vec![storer.join().unwrap(), recycler.join().unwrap()];
+        while let Ok(k) = slots.store(3) {
+            keys.push(k);
+        }
+
+        let mut indices = keys.iter().map(|k| k.index()).collect::<Vec<_>>();
+        indices.sort_unstable();
+        assert_eq!(vec![0, 1, 2], indices);
+
+        let mut values = keys.into_iter().map(|k| slots.take(k)).collect::<Vec<_>>();
+        values.sort_unstable();
+        assert_eq!(vec![0, 2, 3], values);
+    });
+}
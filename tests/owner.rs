@@ -0,0 +1,127 @@
+use slots::error::SlotsError;
+use slots::owner::{NoOwnerId, OwnerIdSource};
+use slots::slots::{Key, Slots};
+
+#[test]
+fn no_owner_id_does_not_verify_keys() {
+    let mut a: Slots<u8, 4, usize, NoOwnerId> = Slots::new();
+    let mut b: Slots<u8, 4, usize, NoOwnerId> = Slots::new();
+
+    let k = a.store(5).expect("There should be room");
+    let _ = b.store(6).expect("There should be room");
+
+    assert_eq!(6, b.take(k));
+}
+
+#[test]
+fn no_owner_id_keys_are_smaller() {
+    // only the index and the generation of the slot
+    assert_eq!(
+        core::mem::size_of::<(usize, u32)>(),
+        core::mem::size_of::<Key<u8, 4, usize, NoOwnerId>>()
+    );
+    // narrow index types use the same type for the generation
+    assert_eq!(2, core::mem::size_of::<Key<u8, 4, u8, NoOwnerId>>());
+    assert_eq!(
+        core::mem::size_of::<Option<Key<u8, 4, u8, NoOwnerId>>>(),
+        core::mem::size_of::<Key<u8, 4, u8, NoOwnerId>>()
+    );
+    assert!(
+        core::mem::size_of::<Key<u8, 4, usize, NoOwnerId>>()
+            < core::mem::size_of::<Key<u8, 4, usize, slots::owner::AtomicOwnerId>>()
+    );
+    assert_eq!(
+        core::mem::size_of::<Option<Key<u8, 4, usize, NoOwnerId>>>(),
+        core::mem::size_of::<Key<u8, 4, usize, NoOwnerId>>()
+    );
+}
+
+fn verifies_keys<O: OwnerIdSource>() {
+    let mut a: Slots<u8, 4, usize, O> = Slots::new();
+    let mut b: Slots<u8, 4, usize, O> = Slots::new();
+
+    let k = a.store(5).expect("There should be room");
+    let _ = b.store(6).expect("There should be room");
+
+    b.take(k);
+}
+
+fn ids_are_unique<O: OwnerIdSource>() {
+    let first = O::next_id();
+    let second = O::next_id();
+
+    assert_ne!(O::UNASSIGNED, first);
+    assert_ne!(O::UNASSIGNED, second);
+    assert_ne!(first, second);
+}
+
+#[test]
+#[should_panic(expected = "Key used in wrong instance")]
+fn atomic_owner_id_verifies_keys() {
+    verifies_keys::<slots::owner::AtomicOwnerId>();
+}
+
+#[test]
+fn atomic_owner_ids_are_unique() {
+    ids_are_unique::<slots::owner::AtomicOwnerId>();
+}
+
+#[test]
+#[cfg(feature = "portable-atomic")]
+#[should_panic(expected = "Key used in wrong instance")]
+fn portable_atomic_owner_id_verifies_keys() {
+    verifies_keys::<slots::owner::PortableAtomicOwnerId>();
+}
+
+#[test]
+#[cfg(feature = "portable-atomic")]
+fn portable_atomic_owner_ids_are_unique() {
+    ids_are_unique::<slots::owner::PortableAtomicOwnerId>();
+}
+
+#[test]
+#[cfg(feature = "critical-section")]
+#[should_panic(expected = "Key used in wrong instance")]
+fn critical_section_owner_id_verifies_keys() {
+    verifies_keys::<slots::owner::CriticalSectionOwnerId>();
+}
+
+#[test]
+#[cfg(feature = "critical-section")]
+fn critical_section_owner_ids_are_unique() {
+    ids_are_unique::<slots::owner::CriticalSectionOwnerId>();
+}
+
+#[test]
+#[should_panic(expected = "Key used in wrong instance")]
+fn no_owner_id_rejects_keys_from_before_clear() {
+    let mut slots: Slots<u8, 4, usize, NoOwnerId> = Slots::new();
+
+    let k = slots.store(5).expect("There should be room");
+    slots.clear();
+    let _ = slots.store(6).expect("There should be room");
+
+    slots.take(k);
+}
+
+#[test]
+fn no_owner_id_generations_are_tracked_per_slot() {
+    let mut slots: Slots<u8, 4, u8, NoOwnerId> = Slots::new();
+
+    let k1 = slots.store(5).expect("There should be room");
+    let k2 = slots.store(6).expect("There should be room");
+    assert_eq!(11, slots.drain().sum::<u8>());
+
+    // the slots are reused, but the keys belong to an earlier generation
+    let k3 = slots.store(7).expect("There should be room");
+    assert_eq!(k1.index(), k3.index());
+    assert_eq!(
+        Err(SlotsError::WrongOwner),
+        slots.try_take(k1).map_err(|e| e.error)
+    );
+    assert_eq!(
+        Err(SlotsError::WrongOwner),
+        slots.try_take(k2).map_err(|e| e.error)
+    );
+    assert_eq!(7, slots.take(k3));
+}
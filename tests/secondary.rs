@@ -0,0 +1,144 @@
+use slots::generational::GenerationalSlots;
+use slots::secondary::SecondarySlots;
+use slots::slots::Slots;
+use slots::unrestricted::UnrestrictedSlots;
+
+#[test]
+fn values_are_attached_to_keys() {
+    let mut primary: Slots<_, 4> = Slots::new();
+    let mut secondary: SecondarySlots<_, 4> = SecondarySlots::new();
+
+    let k1 = primary.store(1).unwrap();
+    let k2 = primary.store(2).unwrap();
+
+    assert_eq!(None, secondary.insert(&k1, "one"));
+    assert!(secondary.contains_key(&k1));
+    assert!(!secondary.contains_key(&k2));
+    assert_eq!(1, secondary.count());
+
+    *secondary.get_mut(&k1).unwrap() = "uno";
+    assert_eq!(Some("uno"), secondary.insert(&k1, "eins"));
+    assert_eq!(1, secondary.count());
+
+    assert_eq!(Some("eins"), secondary.remove(&k1));
+    assert_eq!(None, secondary.remove(&k1));
+    assert_eq!(0, secondary.count());
+}
+
+#[test]
+fn stale_values_are_not_returned() {
+    let mut primary: GenerationalSlots<_, 2> = GenerationalSlots::new();
+    let mut secondary: SecondarySlots<_, 2> = SecondarySlots::new();
+
+    let old = primary.store(1).unwrap();
+    secondary.insert(old, "old");
+    primary.take(old);
+
+    let new = primary.store(2).unwrap();
+    assert_eq!(old.index(), new.index());
+
+    assert_eq!(None, secondary.get(new));
+    assert_eq!(None, secondary.remove(new));
+    assert_eq!(0, secondary.join(&primary).count());
+
+    // storing for the new element replaces the stale value
+    assert_eq!(None, secondary.insert(new, "new"));
+    assert_eq!(None, secondary.get(old));
+    assert_eq!(
+        vec![(&2, &"new")],
+        secondary.join(&primary).collect::<Vec<_>>()
+    );
+    assert_eq!(1, secondary.count());
+}
+
+#[test]
+fn stale_values_of_slots_keys_are_not_returned() {
+    let mut primary: Slots<_, 2> = Slots::new();
+    let mut secondary: SecondarySlots<_, 2> = SecondarySlots::new();
+
+    let old = primary.store(1).unwrap();
+    secondary.insert(&old, "old");
+    let old_index = old.index();
+    primary.take(old);
+
+    let new = primary.store(2).unwrap();
+    assert_eq!(old_index, new.index());
+
+    assert_eq!(None, secondary.get(&new));
+    assert_eq!(0, secondary.join(&primary).count());
+
+    assert_eq!(None, secondary.insert(&new, "new"));
+    assert_eq!(
+        vec![(&2, &"new")],
+        secondary.join(&primary).collect::<Vec<_>>()
+    );
+
+    // values of keys from before the collection was cleared are stale as well
+    primary.clear();
+    let newest = primary.store(3).unwrap();
+    assert_eq!(old_index, newest.index());
+    assert_eq!(None, secondary.get(&newest));
+    assert_eq!(0, secondary.join(&primary).count());
+}
+
+#[test]
+fn join_skips_missing_elements() {
+    let mut primary: UnrestrictedSlots<_, 8> = UnrestrictedSlots::new();
+    let mut secondary: SecondarySlots<_, 8> = SecondarySlots::new();
+
+    let keys: Vec<usize> = (0..8).map(|i| primary.store(i).unwrap()).collect();
+    for &key in keys.iter().step_by(2) {
+        secondary.insert(key, key * 10);
+    }
+    primary.take(keys[0]);
+
+    let mut joined: Vec<_> = secondary
+        .join(&primary)
+        .map(|(&item, &value)| (item, value))
+        .collect();
+    joined.sort_unstable();
+
+    assert_eq!(
+        vec![(2, keys[2] * 10), (4, keys[4] * 10), (6, keys[6] * 10)],
+        joined
+    );
+    assert_eq!(4, secondary.iter().count());
+}
+
+#[test]
+#[should_panic(expected = "Invalid key")]
+fn insert_panics_outside_of_the_collection() {
+    let mut secondary: SecondarySlots<u32, 2> = SecondarySlots::new();
+
+    secondary.insert(2, 5);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn sparse_values_are_attached_to_handles() {
+    use slots::secondary::SparseSecondarySlots;
+
+    let mut primary: GenerationalSlots<_, 1024> = GenerationalSlots::new();
+    let mut secondary = SparseSecondarySlots::new();
+
+    let h1 = primary.store(1).unwrap();
+    let h2 = primary.store(2).unwrap();
+
+    secondary.insert(h1, "one");
+    secondary.insert(h2, "two");
+    assert_eq!(2, secondary.count());
+
+    primary.take(h2);
+    let h3 = primary.store(3).unwrap();
+
+    assert_eq!(Some(&"one"), secondary.get(h1));
+    assert_eq!(None, secondary.get(h3));
+    assert_eq!(
+        vec![(&1, &"one")],
+        secondary.join(&primary).collect::<Vec<_>>()
+    );
+
+    assert_eq!(Some("one"), secondary.remove(h1));
+    assert_eq!(None, secondary.remove(h3));
+    assert_eq!(1, secondary.iter().count());
+}
@@ -0,0 +1,196 @@
+#![cfg(feature = "serde")]
+
+use slots::generational::{GenerationalSlots, Handle};
+use slots::owner::NoOwnerId;
+use slots::slots::Slots;
+use slots::unrestricted::UnrestrictedSlots;
+
+#[test]
+fn unrestricted_round_trip_preserves_indices() {
+    let mut slots: UnrestrictedSlots<u32, 8> = UnrestrictedSlots::new();
+
+    let keys: Vec<usize> = (0..6).map(|i| slots.store(i).unwrap()).collect();
+    slots.take(keys[1]);
+    slots.take(keys[4]);
+
+    let json = serde_json::to_string(&slots).unwrap();
+    let mut restored: UnrestrictedSlots<u32, 8> = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(slots.count(), restored.count());
+    for (i, &k) in keys.iter().enumerate() {
+        assert_eq!(slots.get(k), restored.get(k), "slot {}", i);
+    }
+
+    // the rebuilt stack of released slots hands out every free slot exactly once
+    let mut new_keys = Vec::new();
+    while let Ok(k) = restored.store(100) {
+        assert!(!new_keys.contains(&k));
+        new_keys.push(k);
+    }
+    assert_eq!(4, new_keys.len());
+    assert!(new_keys.contains(&keys[1]));
+    assert!(new_keys.contains(&keys[4]));
+    assert_eq!(8, restored.count());
+
+    for k in new_keys {
+        assert_eq!(Some(100), restored.take(k));
+    }
+    assert_eq!(4, restored.count());
+}
+
+#[test]
+fn round_trip_preserves_the_allocation_order() {
+    let mut slots: UnrestrictedSlots<u32, 200, u8> = UnrestrictedSlots::new();
+    for i in 0..150 {
+        slots.store(i).unwrap();
+    }
+    for idx in [140, 3, 90, 64, 7] {
+        slots.take(idx);
+    }
+
+    let json = serde_json::to_string(&slots).unwrap();
+    let mut restored: UnrestrictedSlots<u32, 200, u8> = serde_json::from_str(&json).unwrap();
+
+    while let Ok(idx) = slots.store(0) {
+        assert_eq!(Ok(idx), restored.store(0));
+    }
+    assert!(restored.is_full());
+}
+
+#[test]
+fn empty_and_full_collections_round_trip() {
+    let slots: UnrestrictedSlots<u32, 4> = UnrestrictedSlots::new();
+    let json = serde_json::to_string(&slots).unwrap();
+    assert_eq!(r#"{"items":[],"free":[]}"#, json);
+
+    let mut restored: UnrestrictedSlots<u32, 4> = serde_json::from_str(&json).unwrap();
+    assert_eq!(0, restored.count());
+    for i in 0..4 {
+        restored.store(i).unwrap();
+    }
+    assert!(restored.is_full());
+
+    let json = serde_json::to_string(&restored).unwrap();
+    let mut full: UnrestrictedSlots<u32, 4> = serde_json::from_str(&json).unwrap();
+    assert!(full.is_full());
+    assert_eq!(Err(5), full.store(5));
+}
+
+#[test]
+fn listed_free_slots_are_handed_out_first() {
+    // the fields may come in any order
+    let json = r#"{"free": [2], "items": [[1, 5]]}"#;
+    let mut slots: UnrestrictedSlots<u32, 4> = serde_json::from_str(json).unwrap();
+    assert_eq!(1, slots.count());
+
+    // the other free slots follow, highest index first
+    assert_eq!(Ok(2), slots.store(6));
+    assert_eq!(Ok(3), slots.store(7));
+    assert_eq!(Ok(0), slots.store(8));
+    assert!(slots.is_full());
+}
+
+#[test]
+fn invalid_indices_are_rejected() {
+    let parse = serde_json::from_str::<UnrestrictedSlots<u32, 4>>;
+
+    assert!(parse(r#"{"items": [[4, 1]], "free": []}"#).is_err());
+    assert!(parse(r#"{"items": [[1, 1], [1, 2]], "free": []}"#).is_err());
+    assert!(parse(r#"{"items": [], "free": [4]}"#).is_err());
+    assert!(parse(r#"{"items": [], "free": [2, 2]}"#).is_err());
+    assert!(parse(r#"{"items": [[1, 1]], "free": [1]}"#).is_err());
+    assert!(parse(r#"{"free": [1], "items": [[1, 1]]}"#).is_err());
+    assert!(parse(r#"{"items": []}"#).is_err());
+}
+
+#[test]
+fn slots_round_trip_rejects_old_keys() {
+    let mut slots: Slots<u32, 4> = Slots::new();
+    let k = slots.store(5).unwrap();
+
+    let json = serde_json::to_string(&slots).unwrap();
+    let mut restored: Slots<u32, 4> = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(Some(&5), restored.try_get(k.index()));
+    assert_eq!(1, restored.count());
+
+    let k2 = restored.store(6).unwrap();
+    assert_ne!(k.index(), k2.index());
+    assert_eq!(6, restored.take(k2));
+
+    assert_eq!(
+        Err(slots::error::SlotsError::WrongOwner),
+        restored.try_read_key(&k, |&e| e)
+    );
+}
+
+#[test]
+fn slots_round_trip_rejects_old_keys_without_owner_ids() {
+    let mut slots: Slots<u32, 4, usize, NoOwnerId> = Slots::new();
+    let k = slots.store(5).unwrap();
+
+    let json = serde_json::to_string(&slots).unwrap();
+    let mut restored: Slots<u32, 4, usize, NoOwnerId> = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(
+        Err(slots::error::SlotsError::WrongOwner),
+        restored.try_read_key(&k, |&e| e)
+    );
+    assert_eq!(
+        Err(slots::error::SlotsError::WrongOwner),
+        restored.try_take(k).map_err(|e| e.error)
+    );
+
+    // the restored element is kept, and new keys are accepted
+    assert_eq!(Some(&5), restored.iter().next());
+    let k2 = restored.store(6).unwrap();
+    assert_eq!(6, restored.take(k2));
+}
+
+#[test]
+fn generational_round_trip_preserves_handles() {
+    let mut slots: GenerationalSlots<u32, 4, u8> = GenerationalSlots::new();
+
+    let stale = slots.store(1).unwrap();
+    slots.take(stale);
+    let live = slots.store(2).unwrap();
+
+    let json = serde_json::to_string(&slots).unwrap();
+    let mut restored: GenerationalSlots<u32, 4, u8> = serde_json::from_str(&json).unwrap();
+
+    let handles: Vec<Handle<u8>> =
+        serde_json::from_str(&serde_json::to_string(&[stale, live]).unwrap()).unwrap();
+    assert_eq!(vec![stale, live], handles);
+
+    assert!(!restored.contains(stale));
+    assert_eq!(Some(&2), restored.get(live));
+
+    // the generation of a vacant slot is preserved as well
+    assert_eq!(Some(2), restored.take(live));
+    let reused = restored.store(3).unwrap();
+    assert!(!restored.contains(live));
+    assert_eq!(Some(&3), restored.get(reused));
+}
+
+#[test]
+fn generational_generations_must_match_capacity() {
+    let json = r#"{"generations": [0, 0, 0], "items": {"items": [], "free": []}}"#;
+    assert!(serde_json::from_str::<GenerationalSlots<u32, 4>>(json).is_err());
+
+    let json = r#"{"generations": [0, 0, 0, 0], "items": {"items": [[0, 5]], "free": []}}"#;
+    let slots: GenerationalSlots<u32, 4> = serde_json::from_str(json).unwrap();
+    assert_eq!(1, slots.count());
+}
+
+#[test]
+fn generational_generations_must_fit_the_generation_type() {
+    let json = r#"{"generations": [256, 0], "items": {"items": [], "free": []}}"#;
+    assert!(serde_json::from_str::<GenerationalSlots<u32, 2, u8>>(json).is_err());
+
+    let json = r#"{"generations": [255, 0], "items": {"items": [[0, 5]], "free": []}}"#;
+    let slots: GenerationalSlots<u32, 2, u8> = serde_json::from_str(json).unwrap();
+    assert_eq!(1, slots.count());
+
+    assert!(serde_json::from_str::<Handle<u8>>("[0, 256]").is_err());
+    assert!(serde_json::from_str::<Handle<u8>>("[255, 0]").is_err());
+}
@@ -0,0 +1,62 @@
+#![cfg(feature = "critical-section")]
+
+use std::thread;
+
+use slots::shared::SharedSlots;
+
+#[test]
+fn key_can_be_used_to_access_value() {
+    let slots: SharedSlots<_, 8> = SharedSlots::new();
+    let k = slots.store(5).unwrap();
+
+    assert_eq!(5, slots.read(&k, |&w| w));
+    slots.modify(&k, |w| *w += 1);
+    assert_eq!(Some(6), slots.try_read(k.index(), |&w| w));
+    assert_eq!(6, slots.take(k));
+    assert_eq!(0, slots.count());
+}
+
+#[test]
+fn store_returns_err_when_full() {
+    let slots: SharedSlots<u8, 1> = SharedSlots::new();
+
+    slots.store(5).unwrap();
+
+    assert!(slots.is_full());
+    assert!(slots.store(6).is_err());
+}
+
+#[test]
+#[cfg(feature = "runtime_checks")]
+#[should_panic(expected = "Key used in wrong instance")]
+fn use_across_slots_verify() {
+    let a: SharedSlots<u8, 4> = SharedSlots::new();
+    let b: SharedSlots<u8, 4> = SharedSlots::new();
+
+    let k = a.store(5).unwrap();
+    let _ = b.store(6).unwrap();
+
+    b.take(k);
+}
+
+#[test]
+fn can_be_used_from_multiple_threads() {
+    static SLOTS: SharedSlots<usize, 64> = SharedSlots::new();
+
+    let threads = (0..4)
+        .map(|t| {
+            thread::spawn(move || {
+                for i in 0..100 {
+                    let k = SLOTS.store(t * 100 + i).unwrap();
+                    assert_eq!(t * 100 + i, SLOTS.take(k));
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for t in threads {
+        t.join().unwrap();
+    }
+
+    assert_eq!(0, SLOTS.count());
+}
@@ -1,4 +1,6 @@
-use slots::slots::Slots;
+use slots::error::SlotsError;
+use slots::slots::{Key, Slots};
+use slots::unrestricted::UnrestrictedSlots;
 
 #[test]
 fn key_can_be_used_to_read_value() {
@@ -68,6 +70,8 @@ fn trying_to_read_deleted_element_returns_none() {
 }
 
 #[test]
+// the test predates clippy's assign_op_pattern lint, keep its original form
+#[allow(clippy::assign_op_pattern)]
 fn elements_can_be_modified_using_key() {
     let mut slots: Slots<u8, 8> = Slots::new();
 
@@ -155,6 +159,61 @@ fn is_compact() {
     );
 }
 
+#[test]
+/// Verify the layout: free slots hold their link in the memory of the value, and the occupancy
+/// bitmap is stored in a separate array, so there is no padding between them.
+fn slot_layout_has_no_padding() {
+    #[allow(unused)]
+    struct TwoNichesIn16Byte {
+        n1: u64,
+        n2: u32,
+        n3: u16,
+        n4: u8,
+        b: bool,
+    }
+
+    // values, occupancy bitmap + top of the free stack, number of touched slots and count
+    let mut expected_size = 32 * 16 + 32 + 3 * core::mem::size_of::<usize>();
+    expected_size += 32 * core::mem::size_of::<u32>(); // the generation of every slot
+    if cfg!(feature = "runtime_checks") {
+        expected_size += core::mem::size_of::<usize>(); // an extra usize for object id
+    }
+    assert_eq!(
+        core::mem::size_of::<Slots<TwoNichesIn16Byte, 32>>(),
+        expected_size
+    );
+
+    // a value smaller than the index takes up the size of the index and a byte of the bitmap
+    assert_eq!(
+        core::mem::size_of::<UnrestrictedSlots<u8, 32, u16>>(),
+        32 * 3 + 3 * 2
+    );
+}
+
+#[test]
+fn stored_elements_are_dropped_exactly_once() {
+    use std::rc::Rc;
+
+    let item = Rc::new(());
+    {
+        let mut slots: Slots<_, 4> = Slots::new();
+
+        let k1 = slots.store(item.clone()).unwrap();
+        let _k2 = slots.store(item.clone()).unwrap();
+        let k3 = slots.store(item.clone()).unwrap();
+        assert_eq!(4, Rc::strong_count(&item));
+
+        drop(slots.take(k1));
+        assert_eq!(3, Rc::strong_count(&item));
+
+        // reuse a released slot
+        let _k4 = slots.store(item.clone()).unwrap();
+        drop(slots.take(k3));
+        assert_eq!(3, Rc::strong_count(&item));
+    }
+    assert_eq!(1, Rc::strong_count(&item));
+}
+
 #[test]
 fn capacity_and_count() {
     let mut slots: Slots<u8, 4> = Slots::new();
@@ -192,3 +251,714 @@ fn zero_sized_collection() {
     assert!(k1.is_err());
     assert!(slots.try_read(0, |_| { 0 }).is_none());
 }
+
+#[test]
+fn optional_key_is_not_larger_than_key() {
+    assert_eq!(
+        core::mem::size_of::<Option<Key<u8, 8>>>(),
+        core::mem::size_of::<Key<u8, 8>>()
+    );
+    assert_eq!(
+        core::mem::size_of::<Option<Key<u8, 8, u8>>>(),
+        core::mem::size_of::<Key<u8, 8, u8>>()
+    );
+}
+
+#[test]
+fn narrow_index_type_reduces_size() {
+    // Every slot takes 2 bytes: one for either the item or the next free index, and one for the
+    // occupancy bitmap
+    assert_eq!(
+        core::mem::size_of::<UnrestrictedSlots<u8, 255, u8>>(),
+        255 * 2 + 3
+    );
+}
+
+#[test]
+fn narrow_index_type_can_address_every_slot() {
+    let mut slots: Slots<u8, 255, u8> = Slots::new();
+    let mut keys = Vec::new();
+
+    while let Ok(k) = slots.store(keys.len() as u8) {
+        keys.push(k);
+    }
+
+    assert_eq!(255, slots.count());
+    for (i, k) in keys.into_iter().enumerate() {
+        assert_eq!(i as u8, slots.take(k));
+    }
+    assert_eq!(0, slots.count());
+}
+
+#[test]
+fn released_slots_are_reused_first() {
+    // the capacity spans several occupancy words, the last one is only partially used
+    let mut slots: UnrestrictedSlots<usize, 200, u8> = UnrestrictedSlots::new();
+
+    // slots that were never used are handed out from the top
+    for i in 0..100 {
+        assert_eq!(Ok(199 - i), slots.store(i));
+    }
+
+    // released slots are reused in LIFO order, before the remaining unused slots
+    for i in [103, 170, 171, 199, 130] {
+        assert!(slots.take(i).is_some());
+    }
+    for i in [130, 199, 171, 170, 103, 99, 98] {
+        assert_eq!(Ok(i), slots.store(i));
+    }
+}
+
+#[test]
+fn random_stores_and_takes_keep_the_collection_consistent() {
+    let mut slots: UnrestrictedSlots<usize, 130, u8> = UnrestrictedSlots::new();
+    let mut stored = std::collections::BTreeSet::new();
+
+    let mut seed = 0x2545_f491_u32;
+    for value in 0..10_000 {
+        seed ^= seed << 13;
+        seed ^= seed >> 17;
+        seed ^= seed << 5;
+
+        let idx = seed as usize % slots.capacity();
+        if seed & 0x100 != 0 {
+            match slots.store(value) {
+                Ok(idx) => assert!(stored.insert(idx)),
+                Err(_) => assert_eq!(slots.capacity(), stored.len()),
+            }
+        } else {
+            assert_eq!(stored.remove(&idx), slots.take(idx).is_some());
+        }
+
+        assert_eq!(stored.len(), slots.count());
+    }
+
+    let indices: Vec<_> = slots.iter_indexed().map(|(idx, _)| idx).collect();
+    assert_eq!(stored.iter().copied().collect::<Vec<_>>(), indices);
+}
+
+#[test]
+fn can_be_created_in_const_context() {
+    static POOL: std::sync::Mutex<Slots<u32, 4>> = std::sync::Mutex::new(Slots::new());
+
+    let k1 = POOL.lock().unwrap().store(1).unwrap();
+    let k2 = POOL.lock().unwrap().store(2).unwrap();
+
+    let mut slots = POOL.lock().unwrap();
+    assert_eq!(2, slots.count());
+    assert_eq!(1, slots.take(k1));
+    assert_eq!(2, slots.take(k2));
+}
+
+#[test]
+#[cfg(feature = "runtime_checks")]
+#[should_panic(expected = "Key used in wrong instance")]
+fn key_is_rejected_by_instance_without_id() {
+    let mut a: Slots<u8, 4> = Slots::new();
+    let b: Slots<u8, 4> = Slots::new();
+
+    let k = a.store(5).expect("There should be room");
+
+    b.read(&k, |_| ());
+}
+
+#[test]
+fn elements_can_be_borrowed_using_key() {
+    let mut slots: Slots<String, 4> = Slots::new();
+
+    let k = slots.store(String::from("foo")).unwrap();
+
+    slots.get_mut(&k).push_str("bar");
+    slots[&k].push('!');
+
+    let borrowed: &str = slots.get(&k);
+    assert_eq!("foobar!", borrowed);
+    assert_eq!("foobar!", slots[&k]);
+}
+
+#[test]
+fn try_get_can_be_used_with_question_mark() {
+    fn length(slots: &Slots<String, 4>, index: usize) -> Option<usize> {
+        let item = slots.try_get(index)?;
+        Some(item.len())
+    }
+
+    let mut slots: Slots<String, 4> = Slots::new();
+    let k = slots.store(String::from("foo")).unwrap();
+
+    assert_eq!(Some(3), length(&slots, k.index()));
+    assert_eq!(None, length(&slots, slots.capacity()));
+}
+
+#[test]
+#[cfg(feature = "runtime_checks")]
+#[should_panic(expected = "Key used in wrong instance")]
+fn indexing_verifies_owner() {
+    let mut a: Slots<u8, 4> = Slots::new();
+    let mut b: Slots<u8, 4> = Slots::new();
+
+    let k = a.store(5).expect("There should be room");
+    let _ = b.store(6).expect("There should be room");
+
+    let _ = b[&k];
+}
+
+#[test]
+fn many_elements_can_be_modified_at_once() {
+    let mut slots: Slots<u32, 4> = Slots::new();
+
+    let k1 = slots.store(10).unwrap();
+    let k2 = slots.store(0).unwrap();
+    let k3 = slots.store(0).unwrap();
+
+    let [from, to1, to2] = slots.get_many_mut([&k1, &k2, &k3]);
+    *to1 += 3;
+    *to2 += 4;
+    *from -= 7;
+
+    assert_eq!(3, slots.read(&k1, |&w| w));
+    assert_eq!(3, slots.read(&k2, |&w| w));
+    assert_eq!(4, slots.read(&k3, |&w| w));
+}
+
+#[test]
+#[cfg(not(feature = "runtime_checks"))]
+#[should_panic(expected = "Invalid key")]
+fn many_keys_aliasing_the_same_slot_are_rejected() {
+    let mut a: Slots<u8, 4> = Slots::new();
+    let mut b: Slots<u8, 4> = Slots::new();
+
+    let ka = a.store(5).expect("There should be room");
+    let kb = b.store(6).expect("There should be room");
+
+    // keys of different instances may address the same slot
+    assert_eq!(ka.index(), kb.index());
+    b.get_many_mut([&ka, &kb]);
+}
+
+#[test]
+fn unrestricted_many_mut_rejects_vacant_and_out_of_bounds_slots() {
+    let mut slots: UnrestrictedSlots<u8, 4> = UnrestrictedSlots::new();
+
+    let k1 = slots.store(1).unwrap();
+    let k2 = slots.store(2).unwrap();
+    slots.take(k2);
+
+    assert!(slots.get_many_mut([k1, k2]).is_none());
+    assert!(slots.get_many_mut([k1, slots.capacity()]).is_none());
+    assert!(slots.get_many_mut([k2, k1, k1]).is_none());
+
+    let [item] = slots.get_many_mut([k1]).unwrap();
+    assert_eq!(1, *item);
+}
+
+#[test]
+fn unrestricted_handles_out_of_bounds_indices() {
+    let mut slots: UnrestrictedSlots<u8, 4> = UnrestrictedSlots::new();
+
+    assert_eq!(None, slots.take(4));
+    assert_eq!(None, slots.read(4, |&e| e));
+    assert_eq!(None, slots.modify(4, |e| *e));
+    assert_eq!(None, slots.get(4));
+    assert_eq!(None, slots.get_mut(4));
+}
+
+#[test]
+fn unrestricted_try_methods_report_errors() {
+    let mut slots: UnrestrictedSlots<u8, 1> = UnrestrictedSlots::new();
+
+    let k = slots.try_store(5).unwrap();
+    assert_eq!(Err(SlotsError::Full(6)), slots.try_store(6));
+    assert_eq!(Ok(5), slots.try_read(k, |&e| e));
+    assert_eq!(Ok(()), slots.try_modify(k, |e| *e += 1));
+    assert_eq!(Ok(6), slots.try_take(k));
+
+    assert_eq!(Err(SlotsError::Vacant), slots.try_take(k));
+    assert_eq!(Err(SlotsError::Vacant), slots.try_read(k, |&e| e));
+    assert_eq!(Err(SlotsError::Vacant), slots.try_modify(k, |e| *e));
+    assert_eq!(Err(SlotsError::OutOfBounds), slots.try_take(1));
+    assert_eq!(Err(SlotsError::OutOfBounds), slots.try_read(1, |&e| e));
+    assert_eq!(Err(SlotsError::OutOfBounds), slots.try_modify(1, |e| *e));
+}
+
+#[test]
+#[cfg(feature = "runtime_checks")]
+fn try_methods_report_wrong_owner() {
+    let mut a: Slots<u8, 4> = Slots::new();
+    let mut b: Slots<u8, 4> = Slots::new();
+
+    let k1 = a.try_store(5).unwrap();
+    let k2 = b.try_store(6).unwrap();
+
+    assert_eq!(Err(SlotsError::WrongOwner), b.try_read_key(&k1, |&e| e));
+    assert_eq!(Err(SlotsError::WrongOwner), b.try_modify(&k1, |e| *e));
+    assert_eq!(
+        Err(SlotsError::WrongOwner),
+        b.try_take(k1).map_err(|e| e.error)
+    );
+
+    assert_eq!(Ok(6), b.try_take(k2).map_err(|e| e.error));
+    assert_eq!(1, a.count());
+}
+
+#[test]
+#[cfg(feature = "runtime_checks")]
+fn failed_take_hands_back_the_key() {
+    let mut a: Slots<u8, 4> = Slots::new();
+    let mut b: Slots<u8, 4> = Slots::new();
+
+    let k = a.store(5).unwrap();
+
+    let error = b.try_take(k).unwrap_err();
+    assert_eq!(SlotsError::WrongOwner, error.error);
+    assert_eq!(1, a.count());
+
+    assert_eq!(Ok(5), a.try_take(error.key).map_err(|e| e.error));
+    assert_eq!(0, a.count());
+}
+
+#[test]
+fn errors_can_be_displayed() {
+    fn assert_error<E: std::error::Error>(_: &E) {}
+
+    let err: SlotsError<u8> = SlotsError::Full(5);
+    assert_error(&err);
+
+    assert_eq!("collection is full", err.to_string());
+    assert_eq!("slot is vacant", SlotsError::<u8>::Vacant.to_string());
+    assert_eq!(
+        "index is out of bounds",
+        SlotsError::<u8>::OutOfBounds.to_string()
+    );
+    assert_eq!(
+        "key used in wrong instance",
+        SlotsError::<u8>::WrongOwner.to_string()
+    );
+}
+
+#[test]
+fn large_collections_can_be_initialized_in_place() {
+    // far larger than the stack of the thread
+    const CAPACITY: usize = 200_000;
+
+    std::thread::Builder::new()
+        .stack_size(64 * 1024)
+        .spawn(|| {
+            let mut memory = Box::new_uninit();
+            let slots: &mut Slots<u64, CAPACITY> = Slots::init_in_place(&mut memory);
+
+            let keys: Vec<_> = (0..CAPACITY as u64)
+                .map(|i| slots.store(i).unwrap())
+                .collect();
+            assert!(slots.is_full());
+
+            for (i, k) in keys.into_iter().enumerate() {
+                assert_eq!(i as u64, slots.take(k));
+            }
+            assert_eq!(0, slots.count());
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+}
+
+#[test]
+#[cfg(feature = "alloc")]
+fn large_collections_can_be_created_on_the_heap() {
+    const CAPACITY: usize = 200_000;
+
+    std::thread::Builder::new()
+        .stack_size(64 * 1024)
+        .spawn(|| {
+            let mut slots: Box<UnrestrictedSlots<u64, CAPACITY>> = UnrestrictedSlots::new_boxed();
+
+            let k = slots.store(5).unwrap();
+            assert_eq!(CAPACITY - 1, k);
+            assert_eq!(Some(5), slots.take(k));
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+}
+
+#[test]
+fn drain_empties_the_collection_even_if_dropped_early() {
+    use std::rc::Rc;
+
+    let item = Rc::new(());
+    let mut slots: UnrestrictedSlots<_, 8> = UnrestrictedSlots::new();
+    for _ in 0..6 {
+        slots.store(item.clone()).unwrap();
+    }
+
+    let mut drain = slots.drain();
+    assert_eq!(6, drain.len());
+    drain.next().unwrap();
+    assert_eq!(5, drain.len());
+    drop(drain);
+
+    assert_eq!(1, Rc::strong_count(&item));
+    assert_eq!(0, slots.count());
+
+    // every slot can be used again
+    let mut keys: Vec<usize> = (0..8).map(|_| slots.store(item.clone()).unwrap()).collect();
+    keys.sort_unstable();
+    keys.dedup();
+    assert_eq!(8, keys.len());
+    assert!(slots.is_full());
+}
+
+#[test]
+fn forgotten_drain_leaves_a_consistent_collection() {
+    let mut slots: UnrestrictedSlots<_, 4> = UnrestrictedSlots::new();
+    for i in 0..4 {
+        slots.store(i).unwrap();
+    }
+
+    let mut drain = slots.drain();
+    let (taken, _) = drain.next().unwrap();
+    std::mem::forget(drain);
+
+    assert_eq!(3, slots.count());
+    assert_eq!(3, slots.iter().count());
+    assert_eq!(Ok(taken), slots.store(10));
+    assert!(slots.is_full());
+}
+
+#[test]
+fn collections_can_be_consumed() {
+    let mut unrestricted: UnrestrictedSlots<_, 4> = UnrestrictedSlots::new();
+    let k1 = unrestricted.store(1).unwrap();
+    let k2 = unrestricted.store(2).unwrap();
+
+    let mut items: Vec<(usize, i32)> = unrestricted.into_iter().collect();
+    items.sort_unstable_by_key(|&(_, item)| item);
+    assert_eq!(vec![(k1, 1), (k2, 2)], items);
+
+    let mut slots: Slots<_, 4> = Slots::new();
+    slots.store(String::from("a")).unwrap();
+    slots.store(String::from("b")).unwrap();
+    slots.store(String::from("c")).unwrap();
+
+    let mut iter = slots.into_iter();
+    assert_eq!(3, iter.len());
+    iter.next().unwrap();
+    // the remaining elements are dropped with the iterator
+    drop(iter);
+
+    let mut slots: Slots<_, 4> = Slots::new();
+    let k = slots.store(1).unwrap();
+    assert_eq!(vec![1], slots.drain().collect::<Vec<_>>());
+    assert_eq!(None, slots.try_get(k.index()));
+}
+
+#[test]
+fn retain_releases_slots_for_reuse() {
+    let mut slots: UnrestrictedSlots<_, 16, u8> = UnrestrictedSlots::new();
+    let keys: Vec<usize> = (0..12).map(|i| slots.store(i).unwrap()).collect();
+
+    let mut visited = 0;
+    slots.retain(|idx, item| {
+        visited += 1;
+        assert_eq!(keys[*item], idx);
+        *item % 3 == 0
+    });
+    assert_eq!(12, visited);
+    assert_eq!(4, slots.count());
+
+    // the released slots and the untouched slots are all handed out again, exactly once
+    let mut reused: Vec<usize> = (0..12).map(|i| slots.store(100 + i).unwrap()).collect();
+    assert!(slots.is_full());
+    reused.sort_unstable();
+    reused.dedup();
+    assert_eq!(12, reused.len());
+    for (i, &key) in keys.iter().enumerate() {
+        if i % 3 == 0 {
+            assert_eq!(Some(&i), slots.get(key));
+            assert!(!reused.contains(&key));
+        }
+    }
+}
+
+#[test]
+fn extract_if_is_lazy() {
+    let mut slots: UnrestrictedSlots<_, 8> = UnrestrictedSlots::new();
+    for i in 0..8 {
+        slots.store(i).unwrap();
+    }
+
+    // stop after the first match
+    let (idx, item) = slots.extract_if(|_, item| *item >= 2).next().unwrap();
+    assert!(item >= 2);
+
+    // only the element that was reached is removed
+    assert_eq!(7, slots.count());
+    assert_eq!(None, slots.get(idx));
+
+    let mut rest: Vec<i32> = slots
+        .extract_if(|_, _| true)
+        .map(|(_, item)| item)
+        .collect();
+    rest.sort_unstable();
+    assert_eq!(7, rest.len());
+    assert!(!rest.contains(&item));
+    assert_eq!(0, slots.count());
+    assert_eq!(8, (0..8).filter(|&i| slots.store(i).is_ok()).count());
+}
+
+#[test]
+fn clear_rejects_keys_from_before_the_call() {
+    use std::rc::Rc;
+
+    let item = Rc::new(());
+    let mut slots: Slots<_, 4> = Slots::new();
+
+    let old = slots.try_store(item.clone()).unwrap();
+    slots.store(item.clone()).unwrap();
+
+    slots.clear();
+    assert_eq!(1, Rc::strong_count(&item));
+    assert_eq!(0, slots.count());
+
+    // the same slot is handed out again, but the old key does not address it
+    let new = slots.try_store(item.clone()).unwrap();
+    assert_eq!(old.index(), new.index());
+
+    assert_eq!(
+        Err(SlotsError::WrongOwner),
+        slots.try_read_key(&old, |_| ())
+    );
+    assert_eq!(
+        Err(SlotsError::WrongOwner),
+        slots.try_take(old).map_err(|e| e.error)
+    );
+    assert!(slots.try_take(new).is_ok());
+}
+
+#[test]
+fn clear_leaves_an_empty_collection_if_a_destructor_panics() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+    use std::rc::Rc;
+
+    #[allow(unused)]
+    struct PanicOnDrop(Rc<()>);
+
+    impl Drop for PanicOnDrop {
+        fn drop(&mut self) {
+            panic!("destructor failed");
+        }
+    }
+
+    let item = Rc::new(());
+    let mut slots: UnrestrictedSlots<_, 200> = UnrestrictedSlots::new();
+    for _ in 0..150 {
+        assert!(slots.store(PanicOnDrop(item.clone())).is_ok());
+    }
+
+    let result = catch_unwind(AssertUnwindSafe(|| slots.clear()));
+    assert!(result.is_err());
+
+    // the first element panicked, the others are leaked instead of being dropped later
+    assert_eq!(0, slots.count());
+    assert!(slots.iter().next().is_none());
+    assert!(slots.take(1).is_none());
+    assert_eq!(150, Rc::strong_count(&item));
+
+    // every slot can be reused
+    for idx in (0..200).rev() {
+        assert_eq!(Some(idx), slots.store(PanicOnDrop(item.clone())).ok());
+    }
+    assert!(slots.is_full());
+    for idx in 0..200 {
+        core::mem::forget(slots.take(idx).unwrap());
+    }
+}
+
+#[test]
+fn drain_rejects_keys_from_before_the_call() {
+    let mut slots: Slots<_, 4> = Slots::new();
+
+    let old = slots.store(1).unwrap();
+    std::mem::forget(slots.drain());
+
+    assert_eq!(
+        Err(SlotsError::WrongOwner),
+        slots.try_take(old).map_err(|e| e.error)
+    );
+}
+
+#[test]
+fn unrestricted_clear_releases_every_slot() {
+    let mut slots: UnrestrictedSlots<_, 4> = UnrestrictedSlots::new();
+    for i in 0..4 {
+        slots.store(i).unwrap();
+    }
+
+    slots.clear();
+
+    assert_eq!(0, slots.count());
+    for i in 0..4 {
+        slots.store(i).unwrap();
+    }
+    assert!(slots.is_full());
+}
+
+#[test]
+fn vacant_entry_reveals_the_index_before_storing() {
+    let mut slots: UnrestrictedSlots<_, 2> = UnrestrictedSlots::new();
+
+    // dropping the entry does not allocate the slot
+    let index = slots.vacant_entry().unwrap().index();
+    assert_eq!(0, slots.count());
+
+    let entry = slots.vacant_entry().unwrap();
+    assert_eq!(index, entry.index());
+    *entry.insert(index) += 10;
+    assert_eq!(Some(&(index + 10)), slots.get(index));
+
+    let entry = slots.vacant_entry().unwrap();
+    let other = entry.index();
+    entry.insert(other);
+    assert_ne!(index, other);
+
+    assert!(slots.is_full());
+    assert!(slots.vacant_entry().is_none());
+
+    // released slots are handed out by vacant entries, too
+    slots.take(index);
+    assert_eq!(index, slots.vacant_entry().unwrap().index());
+}
+
+#[test]
+fn occupied_entry_accesses_and_removes_the_element() {
+    let mut slots: UnrestrictedSlots<_, 4> = UnrestrictedSlots::new();
+    let k = slots.store(1).unwrap();
+
+    assert!(slots.occupied_entry(slots.capacity()).is_none());
+    let free = slots.vacant_entry().unwrap().index();
+    assert!(slots.occupied_entry(free).is_none());
+
+    let mut entry = slots.occupied_entry(k).unwrap();
+    assert_eq!(k, entry.index());
+    assert_eq!(&1, entry.get());
+    assert_eq!(1, entry.insert(2));
+    *entry.into_mut() *= 3;
+    assert_eq!(Some(&6), slots.get(k));
+
+    assert_eq!(6, slots.occupied_entry(k).unwrap().remove());
+    assert_eq!(0, slots.count());
+    assert_eq!(k, slots.vacant_entry().unwrap().index());
+}
+
+#[test]
+fn slots_vacant_entry_returns_a_key() {
+    let mut slots: Slots<_, 2> = Slots::new();
+
+    let entry = slots.vacant_entry().unwrap();
+    let index = entry.index();
+    let key = entry.insert(index);
+
+    assert_eq!(index, key.index());
+    assert_eq!(Some(index), slots.try_read(index, |&x| x));
+    assert_eq!(index, slots.take(key));
+}
+
+#[test]
+fn slots_occupied_entry_accesses_and_removes_the_element() {
+    let mut slots: Slots<_, 4> = Slots::new();
+    let k = slots.store(1).unwrap();
+    let index = k.index();
+
+    let mut entry = slots.occupied_entry(k);
+    assert_eq!(index, entry.index());
+    assert_eq!(&1, entry.get());
+    assert_eq!(1, entry.insert(2));
+    let k = entry.into_key();
+    assert_eq!(&2, slots.get(&k));
+
+    *slots.occupied_entry(k).get_mut() *= 3;
+    assert_eq!(Some(6), slots.try_read(index, |&x| x));
+
+    let k = slots.store(7).unwrap();
+    assert_eq!(7, slots.occupied_entry(k).remove());
+    assert_eq!(1, slots.count());
+}
+
+#[test]
+#[cfg(feature = "runtime_checks")]
+#[should_panic(expected = "Key used in wrong instance")]
+fn slots_occupied_entry_verifies_the_key() {
+    let mut a: Slots<u8, 4> = Slots::new();
+    let mut b: Slots<u8, 4> = Slots::new();
+
+    let k = a.store(1).unwrap();
+    let _ = b.store(2).unwrap();
+
+    b.occupied_entry(k);
+}
+
+#[test]
+fn store_with_passes_the_index_to_the_closure() {
+    let mut slots: UnrestrictedSlots<_, 2> = UnrestrictedSlots::new();
+
+    let k1 = slots.store_with(|index| index * 10).unwrap();
+    let k2 = slots.store_with(|index| index * 10).unwrap();
+    assert_eq!(Some(&(k1 * 10)), slots.get(k1));
+    assert_eq!(Some(&(k2 * 10)), slots.get(k2));
+
+    // the closure is not called if there is no room
+    assert_eq!(None, slots.store_with(|_| unreachable!()));
+
+    let mut slots: Slots<_, 2> = Slots::new();
+    let key = slots.store_with(|key| key.index()).unwrap();
+    assert_eq!(key.index(), slots.take(key));
+}
+
+#[test]
+fn slots_store_with_passes_the_returned_key_to_the_closure() {
+    let mut slots: Slots<_, 2> = Slots::new();
+    let _ = slots.store(0).unwrap();
+
+    let mut offered = None;
+    let key = slots
+        .store_with(|key| {
+            offered = Some(key.index());
+            5
+        })
+        .unwrap();
+    assert_eq!(Some(key.index()), offered);
+    assert_eq!(5, slots.take(key));
+
+    // the closure is not called if there is no room
+    let _ = slots.store(1).unwrap();
+    assert!(slots.store_with(|_| unreachable!()).is_none());
+}
+
+#[test]
+fn store_with_leaves_the_collection_unchanged_if_the_closure_panics() {
+    use std::panic::{catch_unwind, AssertUnwindSafe};
+
+    let mut slots: UnrestrictedSlots<u32, 2> = UnrestrictedSlots::new();
+    let k = slots.store(1).unwrap();
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        slots.store_with(|_| panic!("construction failed"));
+    }));
+    assert!(result.is_err());
+
+    assert_eq!(1, slots.count());
+    assert_eq!(vec![(k, &1)], slots.iter_indexed().collect::<Vec<_>>());
+
+    // the slot the closure was offered is still free
+    let k2 = slots.store(2).unwrap();
+    assert!(slots.is_full());
+    assert_eq!(Some(&2), slots.get(k2));
+
+    let mut slots: Slots<u32, 2> = Slots::new();
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        slots.store_with(|_| panic!("construction failed"));
+    }));
+    assert!(result.is_err());
+    assert_eq!(0, slots.count());
+    assert!(slots.store(3).is_ok());
+}